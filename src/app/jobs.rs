@@ -0,0 +1,295 @@
+use crate::error::AppError;
+use crate::history::JobRecord;
+use crate::neos::jobs::{JobId, JobState};
+use crate::neos::report::parse_solve_report;
+use crate::neos::response::NeosResponse;
+use crate::neos::solver::Solver;
+use crate::scenario::Scenario;
+
+use super::App;
+
+impl App {
+    pub(super) fn handle_neos_response(&mut self, response: NeosResponse) {
+        match response {
+            NeosResponse::Error(e) => self.show_error(&e.to_string()),
+            NeosResponse::Message(msg) => self.neos.response = msg,
+            NeosResponse::Solvers(solvers) => {
+                self.neos.response = format!("{} solvers available", solvers.len());
+                if !solvers.is_empty() && !solvers.contains(&self.solver) {
+                    self.solver = solvers[0].clone();
+                }
+                self.neos.solvers = Some(solvers);
+            }
+            NeosResponse::JobCredentials(id, job_number, job_password) => {
+                let Some(job) = self.jobs.get_mut(id) else {
+                    return;
+                };
+                job.credentials = Some((job_number, job_password.clone()));
+                job.state = JobState::Running;
+
+                self.neos.response = format!(
+                    "Submitted job: (number = {}, password = {})",
+                    job_number, job_password
+                );
+
+                let record = JobRecord {
+                    timestamp: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                    template: job.template.name().to_string(),
+                    solver: job.solver.name().to_string(),
+                    job_number,
+                    password: job_password.clone(),
+                    status: "Submitted".to_string(),
+                    output: None,
+                    scenario: job.scenario.clone(),
+                };
+                self.history.add(record);
+                self.save_history();
+
+                self.neos.poll_job(id, job_number, job_password);
+            }
+            NeosResponse::JobFailed(id, e) => {
+                let msg = e.to_string();
+                self.neos.finish_job(id);
+                self.jobs.fail(id, JobState::Failed, msg.clone());
+                self.update_job_record(id, "Failed".to_string(), None);
+                self.show_error(&msg);
+            }
+            NeosResponse::JobStatus(id, status) => {
+                if let Some(job) = self.jobs.get_mut(id) {
+                    job.status = status.clone();
+                    self.neos.response = format!("Job status: {}", status);
+                }
+                self.update_job_record(id, status, None);
+            }
+            NeosResponse::IntermediateOutput(id, output) => {
+                if self.jobs.selected == Some(id) {
+                    self.neos_output.push_str(&output);
+                }
+                if let Some(job) = self.jobs.get_mut(id) {
+                    job.output.push_str(&output);
+                }
+            }
+            NeosResponse::JobOuput(id, output) => {
+                self.neos.finish_job(id);
+                self.update_job_record(id, "Done".to_string(), Some(output.clone()));
+                self.jobs.finish(id, output);
+
+                if self.jobs.selected == Some(id) {
+                    self.show_job_output(id);
+                }
+            }
+            NeosResponse::KillFailed(id, e) => {
+                self.show_error(&e.to_string());
+                let Some(job) = self.jobs.get_mut(id) else {
+                    return;
+                };
+                job.status.clear();
+
+                // Polling stopped when the kill was requested.
+                if let Some((job_number, job_password)) = job.credentials.clone() {
+                    self.neos.poll_job(id, job_number, job_password);
+                }
+            }
+            NeosResponse::JobKilled(id, msg) => {
+                self.neos.finish_job(id);
+                self.jobs.fail(id, JobState::Killed, msg.clone());
+                self.update_job_record(id, "Killed".to_string(), None);
+                self.neos.response = msg;
+            }
+        }
+    }
+
+    /// Submits the current field and template to NEOS with `solver`.
+    pub(super) fn submit_job(&mut self, solver: Solver) -> Result<JobId, AppError> {
+        let input = self.template.generate_neos_input_string(
+            &self.field,
+            &self.params,
+            &solver,
+            &self.config_editor.config.email,
+            &self.templates,
+        )?;
+
+        let scenario = self.current_scenario();
+        let id = self.jobs.add(self.template.clone(), solver, scenario, None);
+        self.neos.submit_job(id, input);
+
+        Ok(id)
+    }
+
+    /// Submits the current field once per solver and collects the jobs
+    /// for the comparison table.
+    pub(super) fn compare_solvers(&mut self, solvers: &[Solver]) {
+        self.comparison.clear();
+
+        for solver in solvers {
+            match self.submit_job(solver.clone()) {
+                Ok(id) => self.comparison.push(id),
+                Err(e) => return self.handle_app_error(e),
+            }
+        }
+    }
+
+    /// Shows the output of the job on the canvas, switching to the field,
+    /// template and parameters it was submitted with. Only called when the
+    /// user picks the job.
+    pub(super) fn select_job(&mut self, id: JobId) {
+        let Some(job) = self.jobs.get(id) else {
+            return;
+        };
+
+        let template = job.template.clone();
+        let scenario = job.scenario.clone();
+        self.jobs.selected = Some(id);
+
+        if !self.shows_field_of(&scenario) {
+            // One undoable step, so edits made since the submission are
+            // one undo away.
+            match scenario.clone().into_field() {
+                Ok(field) => {
+                    self.field.replace_layout(&field);
+                    self.scenario_path = None;
+                }
+                Err(e) => return self.handle_app_error(e),
+            }
+        }
+
+        self.params = scenario.params;
+        self.template = template;
+        self.show_job_output(id);
+    }
+
+    /// Draws the output of a job, leaving the canvas alone when it has
+    /// been edited since the job was submitted.
+    fn show_job_output(&mut self, id: JobId) {
+        let Some(job) = self.jobs.get(id) else {
+            return;
+        };
+
+        if !self.shows_field_of(&job.scenario) || job.template != self.template {
+            self.neos.response = match job.number() {
+                Some(number) => format!("Job {} finished, select it to show the paths", number),
+                None => "Job finished, select it to show the paths".to_string(),
+            };
+            return;
+        }
+
+        let output = job.output.clone();
+        let report = job.report.clone();
+        let done = job.state == JobState::Done;
+
+        self.field.clear_paths();
+        self.validation.clear();
+        self.solve_report = report;
+
+        if done {
+            match self.field.parse_all_paths(&output) {
+                Ok(_) => self.validate_paths(),
+                Err(e) => self.handle_app_error(e),
+            }
+        }
+        self.neos_output = output;
+    }
+
+    pub(super) fn cancel_job(&mut self, id: JobId) {
+        let Some(job) = self.jobs.get_mut(id) else {
+            return;
+        };
+        let Some((job_number, job_password)) = job.credentials.clone() else {
+            return;
+        };
+
+        job.status = "Cancelling".to_string();
+        self.neos.kill_job(id, job_number, job_password);
+    }
+
+    pub(super) fn attach_job(&mut self, job_number: i32, job_password: String) {
+        let scenario = self.current_scenario();
+        let credentials = Some((job_number, job_password.clone()));
+        let id = self.jobs.add(
+            self.template.clone(),
+            self.solver.clone(),
+            scenario,
+            credentials,
+        );
+
+        self.select_job(id);
+        self.neos.response = format!("Attached to job {}", job_number);
+        self.neos.poll_job(id, job_number, job_password);
+    }
+
+    /// Parses paths and the solve report out of `output` onto the field.
+    fn show_output(&mut self, output: String) {
+        self.solve_report = Some(parse_solve_report(&output));
+        match self.field.parse_all_paths(&output) {
+            Ok(_) => self.validate_paths(),
+            Err(e) => self.handle_app_error(e),
+        }
+        self.neos_output = output;
+    }
+
+    /// Whether the canvas shows the field and parameters `scenario` was
+    /// saved from.
+    fn shows_field_of(&self, scenario: &Scenario) -> bool {
+        self.current_scenario().same_layout(scenario)
+    }
+
+    fn update_job_record(&mut self, id: JobId, status: String, output: Option<String>) {
+        let Some(job_number) = self.jobs.get(id).and_then(|job| job.number()) else {
+            return;
+        };
+
+        if let Some(record) = self.history.find_mut(job_number) {
+            record.status = status;
+            if output.is_some() {
+                record.output = output;
+            }
+            self.save_history();
+        }
+    }
+
+    fn save_history(&mut self) {
+        if let Err(e) = self.history.save() {
+            self.handle_app_error(e);
+        }
+    }
+
+    pub(super) fn open_job_record(&mut self, index: usize, refetch: bool) {
+        let Some(record) = self.history.records.get(index).cloned() else {
+            return;
+        };
+
+        let Some(template) = self.templates.resolve(&record.template) else {
+            return self.handle_app_error(AppError::TemplateNotFound(record.template));
+        };
+        let solver = Solver::from_name(&record.solver)
+            .unwrap_or_else(|| Solver::Other(record.solver.clone()));
+
+        if refetch {
+            let credentials = Some((record.job_number, record.password.clone()));
+            let id = self
+                .jobs
+                .add(template, solver, record.scenario, credentials);
+            self.select_job(id);
+            self.neos
+                .get_final_results(id, record.job_number, record.password);
+            return;
+        }
+
+        self.params.extend(record.scenario.params.clone());
+        match record.scenario.into_field() {
+            Ok(field) => self.field = field,
+            Err(e) => return self.handle_app_error(e),
+        }
+
+        self.template = template;
+        self.solver = solver;
+        self.scenario_path = None;
+        self.jobs.selected = None;
+        self.solve_report = None;
+        self.neos_output.clear();
+
+        if let Some(output) = record.output {
+            self.show_output(output);
+        }
+    }
+}
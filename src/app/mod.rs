@@ -1,57 +1,161 @@
+mod jobs;
 mod mode;
 mod utils;
 
-use eframe::egui::{self, DragValue, Ui, UserData};
+use eframe::egui::{self, DragValue, Key, KeyboardShortcut, Modifiers, Ui, UserData};
 
 use crate::app::mode::Mode;
-use crate::app::utils::color_button;
+use crate::app::utils::{
+    color_button, param_widget, show_comparison_table, show_jobs_table, show_metrics_table,
+    show_solve_report, show_validation_reports, JobAction,
+};
 use crate::config::editor::ConfigEditor;
+use crate::consts::HISTORY_PATH;
 use crate::error::AppError;
+use crate::export::naming::{output_path, ExportContext};
+use crate::export::{raster, svg};
 use crate::field::cell::CellType;
+use crate::field::path::validation::{validate_paths, ValidationReport};
+use crate::field::{MAX_CELL_SIZE, MAX_FIELD_SIZE, MIN_CELL_SIZE};
+use crate::generator::{self, dialog::GeneratorDialog};
+use crate::history::panel::{HistoryAction, HistoryPanel};
+use crate::history::JobHistory;
+use crate::local_solver::{self, disjoint::DisjointStrategy, SearchOptions};
 use crate::neos::api::NeosAPI;
-use crate::neos::response::NeosResponse;
+use crate::neos::dialog::{AttachDialog, CompareDialog};
+use crate::neos::jobs::{JobId, JobManager};
+use crate::neos::report::SolveReport;
 use crate::neos::solver::Solver;
+use crate::scenario::dialog::{ScenarioAction, ScenarioDialog};
+use crate::scenario::Scenario;
+use crate::template::param::ParamValues;
+use crate::template::registry::TemplateRegistry;
 use crate::template::Template;
 use crate::utils::image::*;
 use crate::{field::Field, toast::Toast};
 
+const UNDO_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+const REDO_SHORTCUT: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::Z);
+
 pub struct App {
     field: Field,
     mode: Mode,
     template: Template,
+    /// Values of the template parameters, kept across template switches.
+    params: ParamValues,
     toast: Option<Toast>,
     neos: NeosAPI,
     neos_output: String,
+    validation: Vec<ValidationReport>,
+    solve_report: Option<SolveReport>,
     solver: Solver,
+    disjoint_strategy: DisjointStrategy,
+    export_cell_size: u32,
     config_editor: ConfigEditor,
+    templates: TemplateRegistry,
+    scenario_dialog: ScenarioDialog,
+    generator_dialog: GeneratorDialog,
+    scenario_path: Option<String>,
+    history: JobHistory,
+    history_panel: HistoryPanel,
+    attach_dialog: AttachDialog,
+    jobs: JobManager,
+    compare_dialog: CompareDialog,
+    /// Jobs of the last solver comparison.
+    comparison: Vec<JobId>,
     // Use the flag because the screenshot event arrives in the next frame
     taking_screenshot: bool,
 }
 
 impl Default for App {
     fn default() -> Self {
-        Self {
+        let mut app = Self {
             field: Field::new(),
             mode: Mode::Draw(CellType::Green),
             template: Template::Disabled,
+            params: ParamValues::new(),
             toast: None,
-            neos: NeosAPI::new(),
+            neos: NeosAPI::default(),
             neos_output: String::new(),
+            validation: Vec::new(),
+            solve_report: None,
             solver: Solver::Cbc,
+            disjoint_strategy: DisjointStrategy::Greedy,
+            export_cell_size: 40,
             config_editor: ConfigEditor::new(),
+            templates: TemplateRegistry::builtin(),
+            scenario_dialog: ScenarioDialog::new(),
+            generator_dialog: GeneratorDialog::new(),
+            scenario_path: None,
+            history: JobHistory::default(),
+            history_panel: HistoryPanel::new(),
+            attach_dialog: AttachDialog::new(),
+            jobs: JobManager::new(),
+            compare_dialog: CompareDialog::new(),
+            comparison: Vec::new(),
             taking_screenshot: false,
+        };
+        app.apply_config();
+
+        match JobHistory::load() {
+            Ok(history) => app.history = history,
+            Err(e) => {
+                app.history = JobHistory::fallback(HISTORY_PATH);
+                app.handle_app_error(e);
+            }
         }
+        app
     }
 }
 
 impl eframe::App for App {
     fn update(&mut self, ctx: &eframe::egui::Context, _frame: &mut eframe::Frame) {
+        // Text fields keep the shortcuts for their own undo. Redo first: the
+        // undo shortcut would also match Ctrl+Shift+Z.
+        if !ctx.wants_keyboard_input() {
+            if ctx.input_mut(|i| i.consume_shortcut(&REDO_SHORTCUT)) {
+                self.field.redo();
+            } else if ctx.input_mut(|i| i.consume_shortcut(&UNDO_SHORTCUT)) {
+                self.field.undo();
+            }
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
                 if ui.button("Config").clicked() {
                     self.config_editor.open();
                 }
 
+                if ui.button("Open").clicked() {
+                    self.scenario_dialog.open();
+                }
+
+                if ui.button("Save").clicked() {
+                    match self.scenario_path.clone() {
+                        Some(path) => self.save_scenario(path),
+                        None => self.scenario_dialog.save_as(),
+                    }
+                }
+
+                if ui.button("Save as").clicked() {
+                    self.scenario_dialog.save_as();
+                }
+
+                if ui
+                    .add_enabled(self.field.can_undo(), egui::Button::new("Undo"))
+                    .clicked()
+                {
+                    self.field.undo();
+                }
+
+                if ui
+                    .add_enabled(self.field.can_redo(), egui::Button::new("Redo"))
+                    .clicked()
+                {
+                    self.field.redo();
+                }
+
                 for cell_type in CellType::variants() {
                     if color_button(ui, cell_type.color(), self.mode == Mode::Draw(*cell_type))
                         .clicked()
@@ -63,42 +167,28 @@ impl eframe::App for App {
                 ui.selectable_value(&mut self.mode, Mode::StartSelection, "Start");
                 ui.selectable_value(&mut self.mode, Mode::EndSelection, "Terminal");
 
+                if ui.button("Generate").clicked() {
+                    self.generator_dialog.open();
+                }
+
                 egui::ComboBox::from_label("Template")
-                    .selected_text(self.template.name())
+                    .selected_text(self.templates.display_name(&self.template))
                     .show_ui(ui, |ui| {
-                        for variant in Template::variants() {
-                            ui.selectable_value(&mut self.template, *variant, variant.name());
+                        let custom = self.templates.custom_templates();
+                        for variant in Template::variants().iter().chain(&custom) {
+                            let label = self.templates.display_name(variant);
+                            ui.selectable_value(&mut self.template, variant.clone(), label);
                         }
                     });
 
-                if let Template::TurnCost(value) = &mut self.template {
-                    ui.add(
-                        DragValue::new(value)
-                            .range(0..=10)
-                            .speed(1.0)
-                            .suffix(" Turn cost"),
-                    );
+                if ui.button("Reload templates").clicked() {
+                    self.reload_templates();
                 }
 
-                if let Template::Full {
-                    max_yellow_nodes,
-                    max_orange_nodes,
-                } = &mut self.template
-                {
-                    ui.horizontal(|ui| {
-                        ui.add(
-                            DragValue::new(max_yellow_nodes)
-                                .range(0..=10)
-                                .speed(1.0)
-                                .suffix(" Max yellow"),
-                        );
-                        ui.add(
-                            DragValue::new(max_orange_nodes)
-                                .range(0..=10)
-                                .speed(1.0)
-                                .suffix(" Max orange"),
-                        );
-                    });
+                for spec in self.templates.params(&self.template) {
+                    let mut value = spec.value(&self.params);
+                    param_widget(ui, &spec, &mut value);
+                    self.params.insert(spec.name, value);
                 }
 
                 if ui.button("Clear paths").clicked() {
@@ -112,27 +202,120 @@ impl eframe::App for App {
                 egui::ComboBox::from_label("Solver")
                     .selected_text(self.solver.name())
                     .show_ui(ui, |ui| {
-                        for variant in Solver::variants() {
-                            ui.selectable_value(&mut self.solver, *variant, variant.name());
+                        for variant in self.neos.available_solvers() {
+                            ui.selectable_value(&mut self.solver, variant.clone(), variant.name());
                         }
                     });
 
+                if ui.button("Refresh solvers").clicked() {
+                    self.neos.list_solvers();
+                }
+
                 if ui.button("Send to NEOS").clicked() {
-                    match self.template.generate_neos_input_string(
-                        &self.field,
-                        &self.solver,
-                        &self.config_editor.config.email,
-                    ) {
-                        Ok(input) => {
-                            self.neos.submit_job(input);
-                        }
+                    match self.submit_job(self.solver.clone()) {
+                        Ok(id) => self.select_job(id),
                         Err(e) => self.handle_app_error(e),
                     }
                 }
 
+                if matches!(
+                    self.template,
+                    Template::Multiple | Template::MultipleSections | Template::MultipleSeparated
+                ) {
+                    egui::ComboBox::from_label("Local strategy")
+                        .selected_text(self.disjoint_strategy.name())
+                        .show_ui(ui, |ui| {
+                            for variant in DisjointStrategy::variants() {
+                                ui.selectable_value(
+                                    &mut self.disjoint_strategy,
+                                    *variant,
+                                    variant.name(),
+                                );
+                            }
+                        });
+                }
+
+                let supported = SearchOptions::for_template(&self.template).is_some();
+                if ui
+                    .add_enabled(supported, egui::Button::new("Solve locally"))
+                    .on_disabled_hover_text("The local solver does not model this template")
+                    .clicked()
+                {
+                    self.solve_locally();
+                }
+
+                if ui.button("Compare solvers").clicked() {
+                    self.compare_dialog.open();
+                }
+
+                if ui.button("Attach to job").clicked() {
+                    self.attach_dialog.open();
+                }
+
+                if ui.button("History").clicked() {
+                    self.history_panel.toggle();
+                }
+
                 if ui.button("Screenshot").clicked() {
                     self.taking_screenshot = true;
                 }
+
+                if ui.button("Export SVG").clicked() {
+                    let result = self.export_path("field", "svg").and_then(|path| {
+                        svg::save_svg(&path, &self.field, self.field.cell_size())?;
+                        Ok(path)
+                    });
+                    self.report_export(result);
+                }
+
+                if ui.button("Export PNG").clicked() {
+                    let result = self.export_path("field", "png").and_then(|path| {
+                        raster::save_png(&path, &self.field, self.export_cell_size)?;
+                        Ok(path)
+                    });
+                    self.report_export(result);
+                }
+                ui.add(
+                    DragValue::new(&mut self.export_cell_size)
+                        .range(1..=raster::MAX_EXPORT_CELL_SIZE)
+                        .suffix(" px/cell"),
+                );
+            });
+
+            ui.horizontal(|ui| {
+                let (mut width, mut height) = (self.field.width, self.field.height);
+                let width_changed = ui
+                    .add(
+                        DragValue::new(&mut width)
+                            .range(1..=MAX_FIELD_SIZE)
+                            .speed(1.0)
+                            .suffix(" Width"),
+                    )
+                    .changed();
+                let height_changed = ui
+                    .add(
+                        DragValue::new(&mut height)
+                            .range(1..=MAX_FIELD_SIZE)
+                            .speed(1.0)
+                            .suffix(" Height"),
+                    )
+                    .changed();
+                if width_changed || height_changed {
+                    self.field.resize(width, height);
+                }
+
+                let mut cell_size = self.field.cell_size();
+                if ui
+                    .add(
+                        DragValue::new(&mut cell_size)
+                            .range(MIN_CELL_SIZE..=MAX_CELL_SIZE)
+                            .speed(0.5)
+                            .suffix(" px"),
+                    )
+                    .changed()
+                {
+                    self.field.set_cell_size(cell_size);
+                }
             });
 
             ui.add_space(20.0);
@@ -140,20 +323,50 @@ impl eframe::App for App {
             ui.horizontal(|ui| {
                 self.field.setup(ui);
 
-                if self.neos.is_solving_task {
-                    ui.horizontal(|ui| {
-                        ui.spinner();
-                        ui.label("Solving task");
-                    });
-                } else {
-                    ui.vertical(|ui| {
-                        egui::ScrollArea::vertical()
-                            .max_height(self.field.area_height())
-                            .show(ui, |ui| {
-                                ui.label(&self.neos_output);
-                            });
-                    });
-                }
+                ui.vertical(|ui| {
+                    if !self.jobs.jobs().is_empty() {
+                        match show_jobs_table(ui, &self.jobs) {
+                            Some(JobAction::Select(id)) => self.select_job(id),
+                            Some(JobAction::Cancel(id)) => self.cancel_job(id),
+                            Some(JobAction::Remove(id)) => self.jobs.remove(id),
+                            None => {}
+                        }
+                        ui.add_space(10.0);
+                    }
+
+                    if let Some(report) = &self.solve_report {
+                        show_solve_report(ui, report);
+                        ui.add_space(10.0);
+                    }
+
+                    if let Some(paths) = &self.field.paths {
+                        show_metrics_table(ui, &self.field, paths, self.solve_report.as_ref());
+                        ui.add_space(10.0);
+
+                        if !self.validation.is_empty() {
+                            show_validation_reports(ui, &self.validation);
+                            ui.add_space(10.0);
+                        }
+                    }
+
+                    let solving = self
+                        .jobs
+                        .selected()
+                        .is_some_and(|job| !job.state.is_finished());
+                    if solving {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("Solving task");
+                        });
+                    }
+
+                    egui::ScrollArea::vertical()
+                        .max_height(self.field.area_height())
+                        .stick_to_bottom(solving)
+                        .show(ui, |ui| {
+                            ui.label(&self.neos_output);
+                        });
+                });
             });
 
             match self.field.hovered_cell() {
@@ -175,8 +388,22 @@ impl eframe::App for App {
                 }
             }
 
-            if self.config_editor.is_open() {
-                self.config_editor.show(ui);
+            if self.config_editor.is_open() && self.config_editor.show(ui) {
+                self.apply_config();
+            }
+
+            if self.generator_dialog.is_open() {
+                if let Some(config) = self.generator_dialog.show(ui) {
+                    self.generate_interferences(&config);
+                }
+            }
+
+            if self.scenario_dialog.is_open() {
+                match self.scenario_dialog.show(ui) {
+                    Some(ScenarioAction::Open(path)) => self.open_scenario(path),
+                    Some(ScenarioAction::Save(path)) => self.save_scenario(path),
+                    None => {}
+                }
             }
 
             ui.label(format!("NEOS response :: {}", self.neos.response));
@@ -188,37 +415,54 @@ impl eframe::App for App {
                 Mode::EndSelection => self.field.handle_end_cell_selection(),
             }
 
-            if let Ok(neos_response) = self.neos.rx.try_recv() {
-                match neos_response {
-                    NeosResponse::Error(msg) => {
-                        self.neos.is_solving_task = false;
-                        self.show_error(&msg);
-                    }
-                    NeosResponse::Message(msg) => self.neos.response = msg,
-                    NeosResponse::JobCredentials(job_number, job_password) => {
-                        self.neos.response = format!(
-                            "Submitted job: (number = {}, password = {})",
-                            job_number, job_password
-                        );
-
-                        self.neos.get_final_results(job_number, job_password);
-                    }
-                    NeosResponse::JobOuput(output) => {
-                        self.neos.is_solving_task = false;
-                        match self.field.parse_all_paths(&output) {
-                            Ok(_) => {}
-                            Err(e) => self.handle_app_error(e),
-                        }
-                        self.neos_output = output;
-                    }
+            while let Ok(neos_response) = self.neos.rx.try_recv() {
+                self.handle_neos_response(neos_response);
+            }
+
+            if self.compare_dialog.is_open() {
+                if let Some(solvers) = self.compare_dialog.show(ui, self.neos.available_solvers()) {
+                    self.compare_solvers(&solvers);
+                }
+            }
+
+            if !self.comparison.is_empty() {
+                let mut open = true;
+                let mut action = None;
+                egui::Window::new("Solver comparison")
+                    .open(&mut open)
+                    .show(ui.ctx(), |ui| {
+                        action = show_comparison_table(ui, &self.jobs, &self.comparison);
+                    });
+
+                if let Some(JobAction::Select(id)) = action {
+                    self.select_job(id);
+                }
+                if !open {
+                    self.comparison.clear();
+                }
+            }
+
+            if self.attach_dialog.is_open() {
+                if let Some((job_number, job_password)) = self.attach_dialog.show(ui) {
+                    self.attach_job(job_number, job_password);
+                }
+            }
+
+            if self.history_panel.is_open() {
+                match self.history_panel.show(ui, &self.history) {
+                    Some(HistoryAction::Open(index)) => self.open_job_record(index, false),
+                    Some(HistoryAction::Refetch(index)) => self.open_job_record(index, true),
+                    None => {}
                 }
             }
 
             if self.taking_screenshot {
-                if let Err(err) = self.take_screenshot(ui) {
-                    self.handle_app_error(err);
-                } else {
-                    self.show_success("Screenshot taken successfully");
+                match self.take_screenshot(ui) {
+                    Ok(Some(path)) => {
+                        self.show_success(&format!("Screenshot saved to {}", path));
+                    }
+                    Ok(None) => {}
+                    Err(err) => self.handle_app_error(err),
                 }
             }
         });
@@ -235,31 +479,156 @@ impl App {
     }
 
     fn handle_app_error(&mut self, e: AppError) {
-        match e {
-            AppError::ParseStringError(message) => self.show_error(&message),
-            AppError::StartNotSet => self.show_error("Start not set"),
-            AppError::EndNotSet => self.show_error("End not set"),
-            AppError::InvalidPath => {
-                self.show_error("Invalid path");
+        self.show_error(&e.to_string());
+    }
+
+    /// Points the NEOS client and the template registry at the configured
+    /// locations.
+    fn apply_config(&mut self) {
+        match self.config_editor.config.neos_url() {
+            Ok(url) => {
+                self.neos.set_url(url);
+                self.neos.list_solvers();
             }
-            AppError::FailedRenderFile => {
-                self.show_error("Failed render file");
+            Err(e) => self.handle_app_error(e),
+        }
+
+        self.reload_templates();
+    }
+
+    fn reload_templates(&mut self) {
+        self.templates = TemplateRegistry::load(&self.config_editor.config.template_dir);
+
+        let warnings = self.templates.warnings();
+        if !warnings.is_empty() {
+            let message = warnings
+                .iter()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join("\n");
+            self.show_error(&message);
+        }
+
+        // The selected custom template may have been removed.
+        if self.templates.resolve(self.template.name()).is_none() {
+            self.template = Template::Disabled;
+        }
+    }
+}
+
+impl App {
+    fn export_path(&self, kind: &str, extension: &str) -> Result<String, AppError> {
+        let config = &self.config_editor.config;
+        let context = ExportContext {
+            kind,
+            template: self.template.name(),
+            solver: self.solver.name(),
+            job_number: self.jobs.selected().and_then(|job| job.number()),
+            timestamp: chrono::Local::now(),
+        };
+
+        output_path(
+            &config.output_dir,
+            &config.filename_pattern,
+            &context,
+            extension,
+        )
+        .map(|path| path.to_string_lossy().into_owned())
+    }
+
+    fn report_export(&mut self, result: Result<String, AppError>) {
+        match result {
+            Ok(path) => self.show_success(&format!("Exported {}", path)),
+            Err(e) => self.handle_app_error(e),
+        }
+    }
+
+    /// The field together with the template parameters, as saved to
+    /// scenario files and stored with jobs.
+    fn current_scenario(&self) -> Scenario {
+        Scenario::from_field(&self.field).with_params(self.params.clone())
+    }
+
+    fn validate_paths(&mut self) {
+        let paths = self.field.paths.as_deref().unwrap_or_default();
+        self.validation = validate_paths(paths, &self.field, &self.template, &self.params);
+
+        self.field.highlighted_cells = self
+            .validation
+            .iter()
+            .flat_map(|report| &report.violations)
+            .flat_map(|violation| violation.cells())
+            .collect();
+
+        if !self.validation.is_empty() {
+            self.show_error("Solver returned paths violating the template constraints");
+        }
+    }
+
+    fn solve_locally(&mut self) {
+        match local_solver::solve(
+            &self.field,
+            &self.template,
+            &self.params,
+            self.disjoint_strategy,
+        ) {
+            Ok(paths) => {
+                self.neos_output = format!("Local solver: {} path(s) found", paths.len());
+                self.solve_report = None;
+                self.field.paths = Some(paths);
+                self.validate_paths();
             }
-            AppError::InvalidAuthCredentials => {
-                self.show_error("Invalid auth credentials");
+            Err(e) => self.handle_app_error(e),
+        }
+    }
+
+    fn generate_interferences(&mut self, config: &generator::GeneratorConfig) {
+        match generator::generate(&self.field, config) {
+            Ok(layout) => {
+                self.field
+                    .replace_cells(layout.filled_cells, &layout.pink_pairs);
+                self.show_success(&format!("Generated with seed {}", config.seed));
             }
-            AppError::FailedUpdateConfig => {
-                self.show_error("Failed update config");
+            Err(e) => self.handle_app_error(e),
+        }
+    }
+
+    fn open_scenario(&mut self, path: String) {
+        let scenario = match Scenario::load(&path) {
+            Ok(scenario) => scenario,
+            Err(e) => return self.handle_app_error(e),
+        };
+        let params = scenario.params.clone();
+
+        match scenario.into_field() {
+            Ok(field) => {
+                self.field = field;
+                self.params.extend(params);
+                self.neos_output.clear();
+                self.solve_report = None;
+                self.validation.clear();
+                self.scenario_path = Some(path);
+                self.show_success("Scenario loaded");
             }
-            AppError::FailedTakeScreenshot => {
-                self.show_error("Failed to take screenshot");
+            Err(e) => self.handle_app_error(e),
+        }
+    }
+
+    fn save_scenario(&mut self, path: String) {
+        match self.current_scenario().save(&path) {
+            Ok(_) => {
+                self.scenario_path = Some(path);
+                self.show_success("Scenario saved");
             }
+            Err(e) => self.handle_app_error(e),
         }
     }
 }
 
 impl App {
-    fn take_screenshot(&mut self, ui: &mut Ui) -> Result<(), AppError> {
+    /// Returns the path the screenshot was saved to, or `None` while the
+    /// screenshot event hasn't arrived yet.
+    fn take_screenshot(&mut self, ui: &mut Ui) -> Result<Option<String>, AppError> {
         ui.ctx()
             .send_viewport_cmd(egui::ViewportCommand::Screenshot(UserData::default()));
 
@@ -273,7 +642,7 @@ impl App {
                         None
                     }
                 })
-                .last()
+                .next_back()
         });
 
         if let Some(image) = image {
@@ -286,9 +655,12 @@ impl App {
             )
             .ok_or(AppError::FailedTakeScreenshot)?;
 
-            save_color_image_to_png("screenshot.png", &image)
+            let path = self.export_path("screenshot", "png")?;
+            save_color_image_to_png(&path, &image)?;
+
+            Ok(Some(path))
         } else {
-            Ok(())
+            Ok(None)
         }
     }
 }
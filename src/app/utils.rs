@@ -1,4 +1,25 @@
-use eframe::egui::{Color32, Response, Sense, Stroke, Ui, Vec2};
+use eframe::egui::{
+    Button, Color32, ComboBox, DragValue, Grid, Response, RichText, Sense, Stroke, Ui, Vec2,
+};
+
+use crate::{
+    consts::COLORS,
+    field::{
+        path::{metrics::PathMetrics, validation::ValidationReport, Path},
+        Field,
+    },
+    neos::{
+        jobs::{JobId, JobManager, JobState, NeosJob},
+        report::SolveReport,
+    },
+    template::param::{ParamKind, ParamSpec, ParamValue},
+};
+
+pub enum JobAction {
+    Select(JobId),
+    Cancel(JobId),
+    Remove(JobId),
+}
 
 pub fn color_button(ui: &mut Ui, color: Color32, selected: bool) -> Response {
     let (rect, response) = ui.allocate_exact_size(Vec2::splat(20.0), Sense::click());
@@ -17,3 +38,280 @@ pub fn color_button(ui: &mut Ui, color: Color32, selected: bool) -> Response {
 
     response
 }
+
+pub fn show_metrics_table(
+    ui: &mut Ui,
+    field: &Field,
+    paths: &[Path],
+    report: Option<&SolveReport>,
+) {
+    let metrics = paths
+        .iter()
+        .map(|path| PathMetrics::compute(path, field))
+        .collect::<Vec<_>>();
+
+    let objective = |path: &Path| {
+        report
+            .and_then(|r| r.paths.iter().find(|p| p.number == path.id + 1))
+            .and_then(|p| p.objective)
+    };
+
+    let metrics_row = |ui: &mut Ui, m: &PathMetrics| {
+        ui.label(m.steps.to_string());
+        ui.label(format!("{:.3}", m.euclidean_length));
+        ui.label(format!("{:.1}", m.weighted_cost));
+        ui.label(m.turns.to_string());
+        ui.label(m.yellow_cells.to_string());
+        ui.label(m.orange_cells.to_string());
+    };
+
+    Grid::new("path_metrics")
+        .striped(true)
+        .spacing([12.0, 4.0])
+        .show(ui, |ui| {
+            for header in [
+                "Path",
+                "Steps",
+                "Euclidean",
+                "Weighted",
+                "Turns",
+                "Yellow",
+                "Orange",
+                "Objective",
+            ] {
+                ui.strong(header);
+            }
+            ui.end_row();
+
+            for (path, m) in paths.iter().zip(&metrics) {
+                let color = COLORS[path.id % COLORS.len()];
+                ui.label(
+                    RichText::new(format!("{}", path.id + 1))
+                        .color(color)
+                        .strong(),
+                );
+                metrics_row(ui, m);
+                ui.label(objective(path).map_or("-".to_string(), |o| o.to_string()));
+                ui.end_row();
+            }
+
+            if metrics.len() > 1 {
+                ui.strong("Total");
+                metrics_row(ui, &metrics.iter().copied().sum());
+                ui.end_row();
+            }
+        });
+}
+
+pub fn show_solve_report(ui: &mut Ui, report: &SolveReport) {
+    Grid::new("solve_report")
+        .spacing([12.0, 4.0])
+        .show(ui, |ui| {
+            let mut row = |name: &str, value: Option<String>| {
+                if let Some(value) = value {
+                    ui.strong(name);
+                    ui.label(value);
+                    ui.end_row();
+                }
+            };
+
+            row("Solver", report.solver_banner.clone());
+            row("Status", report.status.clone());
+            row("Paths found", report.paths_found.map(|n| n.to_string()));
+            row(
+                "Stopped at step",
+                report.stopped_at_step.map(|n| n.to_string()),
+            );
+            row(
+                "Solve time",
+                report.total_solve_time.map(|t| format!("{} s", t)),
+            );
+        });
+}
+
+pub fn show_validation_reports(ui: &mut Ui, reports: &[ValidationReport]) {
+    for report in reports {
+        let color = COLORS[report.path_id % COLORS.len()];
+        ui.label(
+            RichText::new(format!("Path {} is invalid:", report.path_id + 1))
+                .color(color)
+                .strong(),
+        );
+
+        for violation in &report.violations {
+            ui.label(RichText::new(format!("  {}", violation)).color(Color32::RED));
+        }
+    }
+}
+
+pub fn show_jobs_table(ui: &mut Ui, jobs: &JobManager) -> Option<JobAction> {
+    let mut action = None;
+
+    Grid::new("neos_jobs")
+        .striped(true)
+        .spacing([12.0, 4.0])
+        .show(ui, |ui| {
+            for header in ["", "Template", "Solver", "Job", "Status", ""] {
+                ui.strong(header);
+            }
+            ui.end_row();
+
+            for job in jobs.jobs() {
+                if ui.radio(jobs.selected == Some(job.id), "").clicked() {
+                    action = Some(JobAction::Select(job.id));
+                }
+                ui.label(job.template.name());
+                ui.label(job.solver.name());
+                ui.label(job.number().map_or("-".to_string(), |n| n.to_string()));
+
+                let status = match job.state {
+                    JobState::Submitting => RichText::new("Submitting"),
+                    JobState::Running => RichText::new(if job.status.is_empty() {
+                        "Running"
+                    } else {
+                        &job.status
+                    }),
+                    JobState::Done => RichText::new("Done").color(Color32::GREEN),
+                    JobState::Failed => RichText::new(&job.status).color(Color32::RED),
+                    JobState::Killed => RichText::new("Killed"),
+                };
+                ui.label(status);
+
+                if job.state.is_finished() {
+                    if ui.small_button("Remove").clicked() {
+                        action = Some(JobAction::Remove(job.id));
+                    }
+                } else if ui
+                    .add_enabled(job.credentials.is_some(), Button::new("Cancel").small())
+                    .clicked()
+                {
+                    action = Some(JobAction::Cancel(job.id));
+                }
+                ui.end_row();
+            }
+        });
+
+    action
+}
+
+/// Lists the jobs of a solver comparison, marking the best result: the most
+/// paths found, then the lowest total objective.
+pub fn show_comparison_table(ui: &mut Ui, jobs: &JobManager, ids: &[JobId]) -> Option<JobAction> {
+    let mut action = None;
+
+    let compared = ids
+        .iter()
+        .filter_map(|id| jobs.get(*id))
+        .collect::<Vec<_>>();
+    let objective = |job: &NeosJob| job.report.as_ref().and_then(|r| r.total_objective());
+    let best = compared
+        .iter()
+        .filter(|job| objective(job).is_some())
+        .copied()
+        .reduce(|best, job| match (&job.report, &best.report) {
+            (Some(report), Some(best_report)) if report.is_better_than(best_report) => job,
+            _ => best,
+        })
+        .map(|job| job.id);
+
+    Grid::new("solver_comparison")
+        .striped(true)
+        .spacing([12.0, 4.0])
+        .show(ui, |ui| {
+            for header in [
+                "Solver",
+                "Job",
+                "Status",
+                "Paths",
+                "Objective",
+                "Solve time",
+                "",
+            ] {
+                ui.strong(header);
+            }
+            ui.end_row();
+
+            for job in compared {
+                let report = job.report.as_ref();
+
+                ui.label(job.solver.name());
+                ui.label(job.number().map_or("-".to_string(), |n| n.to_string()));
+                ui.label(match job.state {
+                    JobState::Done => report
+                        .and_then(|r| r.status.clone())
+                        .unwrap_or_else(|| "Done".to_string()),
+                    JobState::Failed => job.status.clone(),
+                    state => format!("{:?}", state),
+                });
+
+                ui.label(report.map_or("-".to_string(), |r| r.path_count().to_string()));
+
+                match objective(job) {
+                    Some(value) if best == Some(job.id) => {
+                        ui.label(
+                            RichText::new(value.to_string())
+                                .color(Color32::GREEN)
+                                .strong(),
+                        );
+                    }
+                    Some(value) => {
+                        ui.label(value.to_string());
+                    }
+                    None => {
+                        ui.label("-");
+                    }
+                }
+
+                ui.label(
+                    report
+                        .and_then(|r| r.total_solve_time)
+                        .map_or("-".to_string(), |t| format!("{} s", t)),
+                );
+
+                if ui
+                    .add_enabled(
+                        job.state == JobState::Done,
+                        Button::new("Show").selected(jobs.selected == Some(job.id)),
+                    )
+                    .clicked()
+                {
+                    action = Some(JobAction::Select(job.id));
+                }
+                ui.end_row();
+            }
+        });
+
+    action
+}
+
+/// Widget for a manifest parameter, resetting values of the wrong type.
+pub fn param_widget(ui: &mut Ui, spec: &ParamSpec, value: &mut ParamValue) {
+    let suffix = format!(" {}", spec.label());
+
+    match (&spec.kind, &mut *value) {
+        (ParamKind::Int { min, max, .. }, ParamValue::Int(v)) => {
+            ui.add(DragValue::new(v).range(*min..=*max).suffix(suffix));
+        }
+        (ParamKind::Float { min, max, .. }, ParamValue::Float(v)) => {
+            ui.add(
+                DragValue::new(v)
+                    .range(*min..=*max)
+                    .speed(0.1)
+                    .suffix(suffix),
+            );
+        }
+        (ParamKind::Bool { .. }, ParamValue::Bool(v)) => {
+            ui.checkbox(v, spec.label());
+        }
+        (ParamKind::Enum { options, .. }, ParamValue::Text(v)) => {
+            ComboBox::from_label(spec.label())
+                .selected_text(v.as_str())
+                .show_ui(ui, |ui| {
+                    for option in options {
+                        ui.selectable_value(v, option.clone(), option);
+                    }
+                });
+        }
+        _ => *value = spec.default_value(),
+    }
+}
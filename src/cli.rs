@@ -0,0 +1,582 @@
+use std::io::Write;
+
+use email_address::EmailAddress;
+
+use crate::{
+    consts::TEMPLATE_DIR,
+    error::AppError,
+    export::{raster, svg},
+    generator::{self, GeneratorConfig, Strategy},
+    neos::{
+        mock::{MockAnswer, MockNeosServer},
+        solver::Solver,
+    },
+    scenario::Scenario,
+    template::{
+        param::{ParamSpec, ParamValues},
+        registry::TemplateRegistry,
+        Template,
+    },
+};
+
+pub const USAGE: &str = "\
+Usage: interference_generator render --scenario <FILE> --template <NAME> [OPTIONS]
+       interference_generator generate --scenario <FILE> --output <FILE> [OPTIONS]
+       interference_generator export --scenario <FILE> --output <FILE.png|FILE.svg> [OPTIONS]
+       interference_generator mock-neos [OPTIONS]
+
+Render options:
+    --solver <NAME>       NEOS solver (default: cbc)
+    --email <EMAIL>       email sent to NEOS, required for xml output
+    --format <xml|ampl>   what to write (default: xml)
+    --output <FILE>       write to a file instead of stdout
+    --template-dir <DIR>  directory with custom templates (default: ./template)
+    --<PARAM> <VALUE>     template parameter with underscores written as
+                          dashes, e.g. --turn-cost 3 for path_turn_cost or
+                          --max-yellow-nodes 2 for path_full or --ns 20 for
+                          the number of paths of the multiple path
+                          templates. Values saved in the scenario are used
+                          for the rest. --max-yellow and --max-orange are
+                          kept as short forms for path_full
+
+Generate options (size and endpoints are taken from the scenario):
+    --strategy <NAME>     uniform, clusters or walls (default: uniform)
+    --density <F>         obstacle probability for uniform (default: 0.2)
+    --count <N>           number of clusters or walls
+    --radius <N>          cluster radius
+    --gap <N>             wall opening size
+    --seed <N>            RNG seed (default: 0)
+    --yellow <F>          share of yellow obstacles (default: 0.1)
+    --orange <F>          share of orange obstacles (default: 0.1)
+    --pink <N>            number of pink pairs (default: 0)
+
+Export options:
+    --cell-size <N>       pixels (or SVG units) per cell, 1 to 200 (default: 40)
+    --neos-output <FILE>  NEOS output whose paths are drawn on top
+
+Mock NEOS options:
+    --address <ADDR>      address to listen on (default: 127.0.0.1:3333)
+    --output <FILE>       answer every job with this output instead of
+                          solving it with the local solver";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutputFormat {
+    Xml,
+    Ampl,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct RenderArgs {
+    pub scenario: String,
+    pub template: Template,
+    pub solver: Solver,
+    pub email: Option<String>,
+    pub format: OutputFormat,
+    pub output: Option<String>,
+    pub template_dir: String,
+    /// Parameters given on the command line, they override the scenario.
+    pub params: ParamValues,
+}
+
+impl RenderArgs {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, AppError> {
+        let mut scenario = None;
+        let mut template = None;
+        let mut solver = Solver::Cbc;
+        let mut email = None;
+        let mut format = OutputFormat::Xml;
+        let mut output = None;
+        let mut template_dir = TEMPLATE_DIR.to_string();
+        let mut param_flags = Vec::new();
+
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| invalid(format!("Missing value for {}", flag)))
+            };
+
+            match flag.as_str() {
+                "--scenario" => scenario = Some(value()?),
+                "--template" => template = Some(value()?),
+                "--solver" => {
+                    let name = value()?;
+                    solver = Solver::from_name(&name)
+                        .ok_or_else(|| invalid(format!("Unknown solver: {}", name)))?;
+                }
+                "--email" => email = Some(value()?),
+                "--format" => {
+                    format = match value()?.as_str() {
+                        "xml" => OutputFormat::Xml,
+                        "ampl" => OutputFormat::Ampl,
+                        other => return Err(invalid(format!("Unknown format: {}", other))),
+                    }
+                }
+                "--output" => output = Some(value()?),
+                "--template-dir" => template_dir = value()?,
+                other if other.starts_with("--") => {
+                    let value = value()?;
+                    param_flags.push((flag, value));
+                }
+                other => return Err(invalid(format!("Unknown argument: {}", other))),
+            }
+        }
+
+        let name = template.ok_or_else(|| invalid("Missing --template".into()))?;
+        let find_spec = |specs: &[ParamSpec], flag: &str| {
+            specs.iter().find(|spec| spec.matches_flag(flag)).cloned()
+        };
+
+        // Only touch the template directory for custom templates or
+        // parameters a manifest adds.
+        let builtin = Template::from_name(&name);
+        let builtin_specs = builtin.as_ref().map(Template::params).unwrap_or_default();
+        let (template, specs) = match builtin {
+            Some(template)
+                if param_flags
+                    .iter()
+                    .all(|(flag, _)| find_spec(&builtin_specs, flag).is_some()) =>
+            {
+                (template, builtin_specs)
+            }
+            _ => {
+                let templates = TemplateRegistry::load(&template_dir);
+                let template = templates
+                    .resolve(&name)
+                    .ok_or_else(|| invalid(format!("Unknown template: {}", name)))?;
+                let specs = templates.params(&template);
+                (template, specs)
+            }
+        };
+
+        let mut params = ParamValues::new();
+        for (flag, value) in param_flags {
+            let spec = find_spec(&specs, &flag)
+                .ok_or_else(|| invalid(format!("Unknown argument: {}", flag)))?;
+            let value = spec
+                .parse(&value)
+                .ok_or_else(|| invalid(format!("Invalid value for {}: {}", flag, value)))?;
+            params.insert(spec.name, value);
+        }
+
+        if let Some(email) = &email {
+            if !EmailAddress::is_valid(email) {
+                return Err(invalid(format!("Invalid email: {}", email)));
+            }
+        }
+
+        if format == OutputFormat::Xml && email.is_none() {
+            return Err(invalid("Missing --email".into()));
+        }
+
+        Ok(Self {
+            scenario: scenario.ok_or_else(|| invalid("Missing --scenario".into()))?,
+            template,
+            solver,
+            email,
+            format,
+            output,
+            template_dir,
+            params,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct GenerateArgs {
+    pub scenario: String,
+    pub output: String,
+    pub config: GeneratorConfig,
+}
+
+impl GenerateArgs {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, AppError> {
+        let mut scenario = None;
+        let mut output = None;
+        let mut strategy = "uniform".to_string();
+        let mut density = None;
+        let mut count = None;
+        let mut radius = None;
+        let mut gap = None;
+        let mut config = GeneratorConfig::default();
+
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| invalid(format!("Missing value for {}", flag)))?;
+
+            match flag.as_str() {
+                "--scenario" => scenario = Some(value),
+                "--output" => output = Some(value),
+                "--strategy" => strategy = value,
+                "--density" => density = Some(parse_float(&flag, &value)?),
+                "--count" => count = Some(parse_number(&flag, &value)?),
+                "--radius" => radius = Some(parse_number(&flag, &value)?),
+                "--gap" => gap = Some(parse_number(&flag, &value)?),
+                "--seed" => config.seed = parse_number(&flag, &value)? as u64,
+                "--yellow" => config.yellow_share = parse_float(&flag, &value)?,
+                "--orange" => config.orange_share = parse_float(&flag, &value)?,
+                "--pink" => config.pink_pairs = parse_number(&flag, &value)?,
+                other => return Err(invalid(format!("Unknown argument: {}", other))),
+            }
+        }
+
+        config.strategy = match Strategy::variants()
+            .iter()
+            .find(|variant| variant.name().eq_ignore_ascii_case(&strategy))
+        {
+            Some(Strategy::Uniform { density: default }) => Strategy::Uniform {
+                density: density.unwrap_or(*default),
+            },
+            Some(Strategy::Clusters {
+                count: default_count,
+                radius: default_radius,
+            }) => Strategy::Clusters {
+                count: count.unwrap_or(*default_count),
+                radius: radius.unwrap_or(*default_radius),
+            },
+            Some(Strategy::Walls {
+                count: default_count,
+                gap: default_gap,
+            }) => Strategy::Walls {
+                count: count.unwrap_or(*default_count),
+                gap: gap.unwrap_or(*default_gap),
+            },
+            None => return Err(invalid(format!("Unknown strategy: {}", strategy))),
+        };
+
+        Ok(Self {
+            scenario: scenario.ok_or_else(|| invalid("Missing --scenario".into()))?,
+            output: output.ok_or_else(|| invalid("Missing --output".into()))?,
+            config,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ExportArgs {
+    pub scenario: String,
+    pub output: String,
+    pub cell_size: u32,
+    pub neos_output: Option<String>,
+}
+
+impl ExportArgs {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, AppError> {
+        let mut scenario = None;
+        let mut output = None;
+        let mut cell_size = 40;
+        let mut neos_output = None;
+
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| invalid(format!("Missing value for {}", flag)))?;
+
+            match flag.as_str() {
+                "--scenario" => scenario = Some(value),
+                "--output" => output = Some(value),
+                "--cell-size" => {
+                    cell_size = parse_number(&flag, &value)?
+                        .try_into()
+                        .ok()
+                        .filter(|size: &u32| (1..=raster::MAX_EXPORT_CELL_SIZE).contains(size))
+                        .ok_or_else(|| {
+                            invalid(format!(
+                                "--cell-size must be between 1 and {}",
+                                raster::MAX_EXPORT_CELL_SIZE
+                            ))
+                        })?;
+                }
+                "--neos-output" => neos_output = Some(value),
+                other => return Err(invalid(format!("Unknown argument: {}", other))),
+            }
+        }
+
+        let output = output.ok_or_else(|| invalid("Missing --output".into()))?;
+        if !output.ends_with(".png") && !output.ends_with(".svg") {
+            return Err(invalid(format!("Unsupported output format: {}", output)));
+        }
+
+        Ok(Self {
+            scenario: scenario.ok_or_else(|| invalid("Missing --scenario".into()))?,
+            output,
+            cell_size,
+            neos_output,
+        })
+    }
+}
+
+pub fn export(args: &ExportArgs) -> Result<(), AppError> {
+    let mut field = Scenario::load(&args.scenario)?.into_field()?;
+
+    if let Some(path) = &args.neos_output {
+        let output = std::fs::read_to_string(path)
+            .map_err(|_| invalid(format!("Failed to read {}", path)))?;
+        field.parse_all_paths(&output)?;
+    }
+
+    if args.output.ends_with(".svg") {
+        svg::save_svg(&args.output, &field, args.cell_size as f32)
+    } else {
+        raster::save_png(&args.output, &field, args.cell_size)
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MockArgs {
+    pub address: String,
+    pub output: Option<String>,
+}
+
+impl MockArgs {
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Self, AppError> {
+        let mut address = "127.0.0.1:3333".to_string();
+        let mut output = None;
+
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            let value = args
+                .next()
+                .ok_or_else(|| invalid(format!("Missing value for {}", flag)))?;
+
+            match flag.as_str() {
+                "--address" => address = value,
+                "--output" => output = Some(value),
+                other => return Err(invalid(format!("Unknown argument: {}", other))),
+            }
+        }
+
+        Ok(Self { address, output })
+    }
+}
+
+pub fn mock_neos(args: &MockArgs) -> Result<(), AppError> {
+    let answer = match &args.output {
+        Some(path) => MockAnswer::Fixed(
+            std::fs::read_to_string(path)
+                .map_err(|_| invalid(format!("Failed to read {}", path)))?,
+        ),
+        None => MockAnswer::LocalSolver,
+    };
+
+    let runtime =
+        tokio::runtime::Runtime::new().map_err(|e| AppError::FailedStartServer(e.to_string()))?;
+
+    runtime.block_on(async {
+        let server = MockNeosServer::start(&args.address, answer)
+            .await
+            .map_err(|e| AppError::FailedStartServer(e.to_string()))?;
+        eprintln!("Mock NEOS server listening on {}", server.url());
+        server.wait().await;
+        Ok(())
+    })
+}
+
+fn invalid(message: String) -> AppError {
+    AppError::InvalidArguments(message)
+}
+
+fn parse_number(flag: &str, value: &str) -> Result<usize, AppError> {
+    value
+        .parse()
+        .map_err(|_| invalid(format!("Invalid value for {}: {}", flag, value)))
+}
+
+/// Parses a finite float, `NaN` and infinities would reach the generator's
+/// probabilities.
+fn parse_float(flag: &str, value: &str) -> Result<f64, AppError> {
+    value
+        .parse()
+        .ok()
+        .filter(|value: &f64| value.is_finite())
+        .ok_or_else(|| invalid(format!("Invalid value for {}: {}", flag, value)))
+}
+
+pub fn render(args: &RenderArgs) -> Result<String, AppError> {
+    let scenario = Scenario::load(&args.scenario)?;
+    let mut params = scenario.params.clone();
+    params.extend(args.params.clone());
+
+    let field = scenario.into_field()?;
+    let templates = TemplateRegistry::load(&args.template_dir);
+    for warning in templates.warnings() {
+        eprintln!("warning: {}", warning);
+    }
+
+    match args.format {
+        OutputFormat::Ampl => args.template.render_ampl(&field, &params, &templates),
+        OutputFormat::Xml => args.template.generate_neos_input_string(
+            &field,
+            &params,
+            &args.solver,
+            args.email.as_deref().unwrap_or_default(),
+            &templates,
+        ),
+    }
+}
+
+/// Entry point for the headless mode. `args` excludes the program name.
+pub fn run<I: IntoIterator<Item = String>>(args: I) -> Result<(), AppError> {
+    let mut args = args.into_iter();
+
+    match args.next().as_deref() {
+        Some("render") => {
+            let args = RenderArgs::parse(args)?;
+            let rendered = render(&args)?;
+
+            match &args.output {
+                Some(path) => {
+                    std::fs::write(path, rendered).map_err(|_| AppError::FailedWriteOutput)
+                }
+                None => std::io::stdout()
+                    .write_all(rendered.as_bytes())
+                    .map_err(|_| AppError::FailedWriteOutput),
+            }
+        }
+        Some("generate") => {
+            let args = GenerateArgs::parse(args)?;
+            let scenario = Scenario::load(&args.scenario)?;
+            let params = scenario.params.clone();
+            let mut field = scenario.into_field()?;
+            let layout = generator::generate(&field, &args.config)?;
+            field.replace_cells(layout.filled_cells, &layout.pink_pairs);
+
+            Scenario::from_field(&field)
+                .with_params(params)
+                .save(&args.output)
+        }
+        Some("export") => export(&ExportArgs::parse(args)?),
+        Some("mock-neos") => mock_neos(&MockArgs::parse(args)?),
+        Some(other) => Err(invalid(format!("Unknown command: {}", other))),
+        None => Err(invalid("Missing command".into())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::param::ParamValue;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_parse_render_args() {
+        let parsed = RenderArgs::parse(args(
+            "--scenario a.toml --template path_turn_cost --solver highs --email a@b.com --turn-cost 3",
+        ))
+        .unwrap();
+
+        assert_eq!(parsed.scenario, "a.toml");
+        assert_eq!(parsed.template, Template::TurnCost);
+        assert_eq!(parsed.params["turn_cost"], ParamValue::Int(3));
+        assert_eq!(parsed.solver, Solver::Highs);
+        assert_eq!(parsed.format, OutputFormat::Xml);
+        assert_eq!(parsed.output, None);
+    }
+
+    #[test]
+    fn test_legacy_flags() {
+        let parsed = RenderArgs::parse(args(
+            "--scenario a.toml --template path_full --format ampl --max-yellow 2 --max-orange 1",
+        ))
+        .unwrap();
+        assert_eq!(parsed.template, Template::Full);
+        assert_eq!(parsed.params["max_yellow_nodes"], ParamValue::Int(2));
+        assert_eq!(parsed.params["max_orange_nodes"], ParamValue::Int(1));
+    }
+
+    #[test]
+    fn test_max_paths() {
+        let parsed = RenderArgs::parse(args(
+            "--scenario a.toml --template path_multiple --format ampl --ns 20",
+        ))
+        .unwrap();
+        assert_eq!(parsed.params["Ns"], ParamValue::Int(20));
+
+        let result = RenderArgs::parse(args(
+            "--scenario a.toml --template path_multiple --format ampl --ns 51",
+        ));
+        assert!(matches!(result, Err(AppError::InvalidArguments(_))));
+    }
+
+    #[test]
+    fn test_invalid_param() {
+        let result = RenderArgs::parse(args(
+            "--scenario a.toml --template path_turn_cost --format ampl --turn-cost 99",
+        ));
+        assert!(matches!(result, Err(AppError::InvalidArguments(_))));
+
+        let result = RenderArgs::parse(args(
+            "--scenario a.toml --template path --format ampl --turn-cost 3",
+        ));
+        assert!(matches!(result, Err(AppError::InvalidArguments(_))));
+    }
+
+    #[test]
+    fn test_xml_requires_email() {
+        let result = RenderArgs::parse(args("--scenario a.toml --template path"));
+        assert!(matches!(result, Err(AppError::InvalidArguments(_))));
+
+        let result = RenderArgs::parse(args("--scenario a.toml --template path --format ampl"));
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_unknown_template() {
+        let result = RenderArgs::parse(args("--scenario a.toml --template nope --format ampl"));
+        assert!(matches!(result, Err(AppError::InvalidArguments(_))));
+    }
+
+    #[test]
+    fn test_parse_generate_args() {
+        let parsed = GenerateArgs::parse(args(
+            "--scenario a.toml --output b.toml --strategy walls --gap 3 --seed 9",
+        ))
+        .unwrap();
+
+        assert_eq!(parsed.config.seed, 9);
+        assert_eq!(parsed.config.strategy, Strategy::Walls { count: 3, gap: 3 });
+    }
+
+    #[test]
+    fn test_non_finite_density() {
+        for value in ["NaN", "inf", "-inf"] {
+            let result = GenerateArgs::parse(args(&format!(
+                "--scenario a.toml --output b.toml --density {}",
+                value
+            )));
+            assert!(matches!(result, Err(AppError::InvalidArguments(_))));
+        }
+
+        let result = GenerateArgs::parse(args("--scenario a.toml --output b.toml --yellow NaN"));
+        assert!(matches!(result, Err(AppError::InvalidArguments(_))));
+    }
+
+    #[test]
+    fn test_cell_size_bounds() {
+        let parsed = ExportArgs::parse(args("--scenario a.toml --output a.png --cell-size 200"));
+        assert_eq!(parsed.unwrap().cell_size, 200);
+
+        for size in ["0", "201", "4294967336"] {
+            let result = ExportArgs::parse(args(&format!(
+                "--scenario a.toml --output a.png --cell-size {}",
+                size
+            )));
+            assert!(matches!(result, Err(AppError::InvalidArguments(_))));
+        }
+    }
+
+    #[test]
+    fn test_parse_mock_args() {
+        let parsed = MockArgs::parse(args("--address 0.0.0.0:4000")).unwrap();
+        assert_eq!(parsed.address, "0.0.0.0:4000");
+        assert_eq!(parsed.output, None);
+
+        assert!(MockArgs::parse(args("--port 1")).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use dxr_client::Url;
 use eframe::egui::{Color32, Id, Modal, RichText, Ui};
 use email_address::EmailAddress;
 
@@ -7,6 +8,10 @@ pub struct ConfigEditor {
     pub config: Config,
     open: bool,
     email_buffer: String,
+    output_dir_buffer: String,
+    filename_pattern_buffer: String,
+    neos_url_buffer: String,
+    template_dir_buffer: String,
     error_msg: String,
 }
 
@@ -16,6 +21,10 @@ impl ConfigEditor {
 
         Self {
             email_buffer: config.email.clone(),
+            output_dir_buffer: config.output_dir.clone(),
+            filename_pattern_buffer: config.filename_pattern.clone(),
+            neos_url_buffer: config.neos_url.clone(),
+            template_dir_buffer: config.template_dir.clone(),
             config,
             open: false,
             error_msg: String::new(),
@@ -35,7 +44,10 @@ impl ConfigEditor {
         self.error_msg = String::new();
     }
 
-    pub fn show(&mut self, ui: &mut Ui) {
+    /// Returns `true` once the config has been saved.
+    pub fn show(&mut self, ui: &mut Ui) -> bool {
+        let mut saved = false;
+
         Modal::new(Id::new("CONFIG MODAL")).show(ui.ctx(), |ui| {
             ui.set_width(250.0);
 
@@ -44,6 +56,19 @@ impl ConfigEditor {
             ui.label("Email:");
             ui.text_edit_singleline(&mut self.email_buffer);
 
+            ui.label("Output directory:");
+            ui.text_edit_singleline(&mut self.output_dir_buffer);
+
+            ui.label("File name pattern:");
+            ui.text_edit_singleline(&mut self.filename_pattern_buffer);
+            ui.small("{kind} {template} {solver} {job} {timestamp}");
+
+            ui.label("NEOS endpoint:");
+            ui.text_edit_singleline(&mut self.neos_url_buffer);
+
+            ui.label("Template directory:");
+            ui.text_edit_singleline(&mut self.template_dir_buffer);
+
             ui.add_space(10.0);
 
             if !self.error_msg.is_empty() {
@@ -53,12 +78,21 @@ impl ConfigEditor {
 
             ui.horizontal(|ui| {
                 if ui.button("Save").clicked() {
-                    if EmailAddress::is_valid(&self.email_buffer) {
+                    if !EmailAddress::is_valid(&self.email_buffer) {
+                        self.error_msg = "Invalid email".to_string();
+                    } else if self.filename_pattern_buffer.trim().is_empty() {
+                        self.error_msg = "Empty file name pattern".to_string();
+                    } else if Url::parse(self.neos_url_buffer.trim()).is_err() {
+                        self.error_msg = "Invalid NEOS endpoint".to_string();
+                    } else {
                         self.config.email = self.email_buffer.clone();
+                        self.config.output_dir = self.output_dir_buffer.clone();
+                        self.config.filename_pattern = self.filename_pattern_buffer.clone();
+                        self.config.neos_url = self.neos_url_buffer.trim().to_string();
+                        self.config.template_dir = self.template_dir_buffer.clone();
                         let _ = self.config.save();
+                        saved = true;
                         self.close();
-                    } else {
-                        self.error_msg = "Invalid email".to_string();
                     }
                 }
 
@@ -69,6 +103,8 @@ impl ConfigEditor {
                 }
             });
         });
+
+        saved
     }
 }
 
@@ -1,12 +1,38 @@
 pub mod editor;
 
+use dxr_client::Url;
 use serde::{Deserialize, Serialize};
 
-use crate::{consts::CONFIG_PATH, error::AppError};
+use crate::{
+    consts::{CONFIG_PATH, NEOS_API_URL, TEMPLATE_DIR},
+    error::AppError,
+    export::naming::DEFAULT_FILENAME_PATTERN,
+};
 
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub email: String,
+    /// Directory screenshots and exports are written to.
+    pub output_dir: String,
+    /// File name pattern for exports, see [`crate::export::naming::render_filename`].
+    pub filename_pattern: String,
+    /// XML-RPC endpoint, e.g. a local `mock-neos` server for offline use.
+    pub neos_url: String,
+    /// Directory the `.tera` templates and their manifests are loaded from.
+    pub template_dir: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            email: String::new(),
+            output_dir: ".".to_string(),
+            filename_pattern: DEFAULT_FILENAME_PATTERN.to_string(),
+            neos_url: NEOS_API_URL.to_string(),
+            template_dir: TEMPLATE_DIR.to_string(),
+        }
+    }
 }
 
 impl Config {
@@ -17,6 +43,10 @@ impl Config {
         config
     }
 
+    pub fn neos_url(&self) -> Result<Url, AppError> {
+        Url::parse(&self.neos_url).map_err(|_| AppError::InvalidNeosUrl(self.neos_url.clone()))
+    }
+
     pub fn save(&self) -> Result<(), AppError> {
         let toml_str = toml::to_string_pretty(self).unwrap();
         std::fs::write(CONFIG_PATH, toml_str).map_err(|_| AppError::FailedUpdateConfig)
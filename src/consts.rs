@@ -1,7 +1,16 @@
+use std::time::Duration;
+
 use eframe::egui::Color32;
 
 pub const CONFIG_PATH: &str = "./config.toml";
+pub const HISTORY_PATH: &str = "./history.toml";
+pub const TEMPLATE_DIR: &str = "./template";
 pub const NEOS_API_URL: &str = "https://neos-server.org:3333";
+pub const NEOS_CATEGORY: &str = "milp";
+pub const NEOS_INPUT_TYPE: &str = "AMPL";
+pub const NEOS_POLL_INTERVAL: Duration = Duration::from_secs(2);
+pub const NEOS_MAX_POLL_BACKOFF: Duration = Duration::from_secs(60);
+pub const NEOS_CALL_TIMEOUT: Duration = Duration::from_secs(30);
 pub const COLORS: [Color32; 14] = [
     Color32::BLACK,
     Color32::DARK_GRAY,
@@ -1,11 +1,76 @@
+use std::fmt;
+
+use crate::field::cell::CellType;
+
 #[derive(Debug)]
 pub enum AppError {
     InvalidPath,
     ParseStringError(String),
     StartNotSet,
     EndNotSet,
-    FailedRenderFile,
+    StartIsEnd,
+    FailedRenderTemplate(String, String),
     InvalidAuthCredentials,
     FailedUpdateConfig,
     FailedTakeScreenshot,
+    FailedLoadScenario,
+    FailedSaveScenario,
+    InvalidScenario,
+    UnsupportedScenarioVersion(u32),
+    InvalidArguments(String),
+    FailedWriteOutput,
+    NoPathFound,
+    UnsupportedTemplate(String),
+    FailedExport,
+    FailedSaveHistory,
+    FailedLoadHistory(String),
+    InvalidNeosUrl(String),
+    FailedStartServer(String),
+    FailedLoadTemplates(String),
+    TemplateNotFound(String),
+    UnsupportedCellType(String, CellType),
 }
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::InvalidPath => write!(f, "Invalid path"),
+            AppError::ParseStringError(message) => write!(f, "{}", message),
+            AppError::StartNotSet => write!(f, "Start not set"),
+            AppError::EndNotSet => write!(f, "End not set"),
+            AppError::StartIsEnd => write!(f, "Start and terminal are the same cell"),
+            AppError::FailedRenderTemplate(name, e) => {
+                write!(f, "Failed to render template {}: {}", name, e)
+            }
+            AppError::InvalidAuthCredentials => write!(f, "Invalid auth credentials"),
+            AppError::FailedUpdateConfig => write!(f, "Failed update config"),
+            AppError::FailedTakeScreenshot => write!(f, "Failed to take screenshot"),
+            AppError::FailedLoadScenario => write!(f, "Failed to load scenario"),
+            AppError::FailedSaveScenario => write!(f, "Failed to save scenario"),
+            AppError::InvalidScenario => write!(f, "Invalid scenario"),
+            AppError::UnsupportedScenarioVersion(version) => {
+                write!(f, "Unsupported scenario version: {}", version)
+            }
+            AppError::InvalidArguments(message) => write!(f, "{}", message),
+            AppError::FailedWriteOutput => write!(f, "Failed to write output"),
+            AppError::NoPathFound => write!(f, "No path found"),
+            AppError::UnsupportedTemplate(name) => {
+                write!(f, "Template {} is not supported by the local solver", name)
+            }
+            AppError::FailedExport => write!(f, "Failed to export"),
+            AppError::FailedSaveHistory => write!(f, "Failed to save job history"),
+            AppError::FailedLoadHistory(e) => write!(f, "Failed to load job history: {}", e),
+            AppError::InvalidNeosUrl(url) => write!(f, "Invalid NEOS endpoint URL: {}", url),
+            AppError::FailedStartServer(e) => write!(f, "Failed to start server: {}", e),
+            AppError::TemplateNotFound(name) => {
+                write!(f, "Template {} is not in the template directory", name)
+            }
+            AppError::FailedLoadTemplates(e) => write!(f, "Failed to load templates: {}", e),
+            AppError::UnsupportedCellType(template, cell_type) => {
+                write!(f, "{} does not support {:?} cells", template, cell_type)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
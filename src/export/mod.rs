@@ -0,0 +1,3 @@
+pub mod naming;
+pub mod raster;
+pub mod svg;
@@ -0,0 +1,116 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local};
+
+use crate::error::AppError;
+
+pub const DEFAULT_FILENAME_PATTERN: &str = "{kind}_{template}_{timestamp}";
+
+/// Values available to filename patterns.
+pub struct ExportContext<'a> {
+    /// What is exported, e.g. `screenshot` or `field`.
+    pub kind: &'a str,
+    pub template: &'a str,
+    pub solver: &'a str,
+    pub job_number: Option<i32>,
+    pub timestamp: DateTime<Local>,
+}
+
+fn sanitize(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Expands `{kind}`, `{template}`, `{solver}`, `{job}` and `{timestamp}`
+/// in `pattern`. `{job}` becomes `nojob` when nothing was submitted yet.
+pub fn render_filename(pattern: &str, context: &ExportContext) -> String {
+    let job = context
+        .job_number
+        .map_or("nojob".to_string(), |n| n.to_string());
+    let timestamp = context.timestamp.format("%Y%m%d-%H%M%S").to_string();
+
+    let name = pattern
+        .replace("{kind}", context.kind)
+        .replace("{template}", context.template)
+        .replace("{solver}", context.solver)
+        .replace("{job}", &job)
+        .replace("{timestamp}", &timestamp);
+
+    sanitize(&name)
+}
+
+/// Path inside `output_dir` for the given pattern and extension. The
+/// directory is created if needed, and a numeric suffix is added when a
+/// file with the same name already exists.
+pub fn output_path(
+    output_dir: &str,
+    pattern: &str,
+    context: &ExportContext,
+    extension: &str,
+) -> Result<PathBuf, AppError> {
+    let dir = Path::new(output_dir);
+    std::fs::create_dir_all(dir).map_err(|_| AppError::FailedExport)?;
+
+    let name = render_filename(pattern, context);
+    let mut path = dir.join(format!("{}.{}", name, extension));
+    let mut counter = 1;
+
+    while path.exists() {
+        path = dir.join(format!("{}_{}.{}", name, counter, extension));
+        counter += 1;
+    }
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn context(job_number: Option<i32>) -> ExportContext<'static> {
+        ExportContext {
+            kind: "field",
+            template: "path_8",
+            solver: "fico-xpress",
+            job_number,
+            timestamp: Local.with_ymd_and_hms(2025, 5, 4, 13, 7, 9).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_render_filename() {
+        assert_eq!(
+            render_filename(
+                "{kind}_{template}_{solver}_{job}_{timestamp}",
+                &context(Some(42))
+            ),
+            "field_path_8_fico-xpress_42_20250504-130709"
+        );
+        assert_eq!(
+            render_filename("{job}/{kind}", &context(None)),
+            "nojob_field"
+        );
+    }
+
+    #[test]
+    fn test_output_path_avoids_clobbering() {
+        let dir = std::env::temp_dir().join(format!("naming_test_{}", std::process::id()));
+        let dir = dir.to_str().unwrap();
+
+        let first = output_path(dir, "{kind}", &context(None), "png").unwrap();
+        std::fs::write(&first, b"").unwrap();
+        let second = output_path(dir, "{kind}", &context(None), "png").unwrap();
+
+        assert!(first.ends_with("field.png"));
+        assert!(second.ends_with("field_1.png"));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,186 @@
+use eframe::egui::Color32;
+use image::{Rgba, RgbaImage};
+
+use crate::{
+    consts::COLORS,
+    error::AppError,
+    field::{cell::Cell, Field},
+};
+
+/// Largest export resolution offered in the app and on the command line.
+pub const MAX_EXPORT_CELL_SIZE: u32 = 200;
+
+/// 5x7 glyphs for the endpoint labels, one row per byte, MSB on the left.
+const GLYPH_S: [u8; 7] = [
+    0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110,
+];
+const GLYPH_T: [u8; 7] = [
+    0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100,
+];
+
+fn rgba(color: Color32) -> Rgba<u8> {
+    Rgba(color.to_array())
+}
+
+fn put_pixel(image: &mut RgbaImage, x: i64, y: i64, color: Rgba<u8>) {
+    if x >= 0 && y >= 0 && (x as u32) < image.width() && (y as u32) < image.height() {
+        image.put_pixel(x as u32, y as u32, color);
+    }
+}
+
+fn fill_rect(image: &mut RgbaImage, x0: i64, y0: i64, x1: i64, y1: i64, color: Rgba<u8>) {
+    for y in y0..y1 {
+        for x in x0..x1 {
+            put_pixel(image, x, y, color);
+        }
+    }
+}
+
+fn fill_circle(image: &mut RgbaImage, (cx, cy): (f32, f32), radius: f32, color: Rgba<u8>) {
+    let r = radius.ceil() as i64;
+    for y in cy as i64 - r..=cy as i64 + r {
+        for x in cx as i64 - r..=cx as i64 + r {
+            let (dx, dy) = (x as f32 + 0.5 - cx, y as f32 + 0.5 - cy);
+            if dx * dx + dy * dy <= radius * radius {
+                put_pixel(image, x, y, color);
+            }
+        }
+    }
+}
+
+/// Segment from `a` to `b` with round caps, so consecutive segments join
+/// without gaps.
+fn draw_line(image: &mut RgbaImage, a: (f32, f32), b: (f32, f32), width: f32, color: Rgba<u8>) {
+    let half = width / 2.0;
+    let (min_x, max_x) = (a.0.min(b.0) - half, a.0.max(b.0) + half);
+    let (min_y, max_y) = (a.1.min(b.1) - half, a.1.max(b.1) + half);
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let length_sq = dx * dx + dy * dy;
+
+    for y in min_y.floor() as i64..=max_y.ceil() as i64 {
+        for x in min_x.floor() as i64..=max_x.ceil() as i64 {
+            let (px, py) = (x as f32 + 0.5, y as f32 + 0.5);
+            let t = if length_sq == 0.0 {
+                0.0
+            } else {
+                (((px - a.0) * dx + (py - a.1) * dy) / length_sq).clamp(0.0, 1.0)
+            };
+            let (ex, ey) = (px - (a.0 + t * dx), py - (a.1 + t * dy));
+            if ex * ex + ey * ey <= half * half {
+                put_pixel(image, x, y, color);
+            }
+        }
+    }
+}
+
+fn draw_glyph(image: &mut RgbaImage, glyph: &[u8; 7], (cx, cy): (f32, f32), height: f32) {
+    let scale = (height / 7.0).max(1.0);
+    let x0 = cx - 2.5 * scale;
+    let y0 = cy - 3.5 * scale;
+
+    for (row, bits) in glyph.iter().enumerate() {
+        for col in 0..5 {
+            if bits & (0b10000 >> col) != 0 {
+                let x = x0 + col as f32 * scale;
+                let y = y0 + row as f32 * scale;
+                fill_rect(
+                    image,
+                    x.round() as i64,
+                    y.round() as i64,
+                    (x + scale).round() as i64,
+                    (y + scale).round() as i64,
+                    rgba(Color32::BLACK),
+                );
+            }
+        }
+    }
+}
+
+/// Rasterizes `field` with its paths at `cell_size` pixels per cell, using
+/// the same colors as the canvas.
+pub fn render_field(field: &Field, cell_size: u32) -> Result<RgbaImage, AppError> {
+    let pixels = |cells: usize| {
+        u32::try_from(cells)
+            .ok()
+            .and_then(|cells| cells.checked_mul(cell_size))
+            .ok_or(AppError::FailedExport)
+    };
+
+    let size = cell_size as f32;
+    let mut image = RgbaImage::new(pixels(field.width)?, pixels(field.height)?);
+    let center = |cell: &Cell| ((cell.x as f32 - 0.5) * size, (cell.y as f32 - 0.5) * size);
+    let border = ((size / 20.0).round() as i64).max(1);
+
+    for y in 1..=field.height {
+        for x in 1..=field.width {
+            let color = match field.filled_cells.get(&Cell::new(x, y)) {
+                Some(cell_type) => cell_type.color(),
+                None => Color32::LIGHT_GRAY,
+            };
+            let x0 = (x - 1) as i64 * cell_size as i64;
+            let y0 = (y - 1) as i64 * cell_size as i64;
+            let x1 = x0 + cell_size as i64;
+            let y1 = y0 + cell_size as i64;
+
+            fill_rect(&mut image, x0, y0, x1, y1, rgba(Color32::GRAY));
+            fill_rect(
+                &mut image,
+                x0 + border,
+                y0 + border,
+                x1 - border,
+                y1 - border,
+                rgba(color),
+            );
+        }
+    }
+
+    for path in field.paths.iter().flatten() {
+        let color = rgba(COLORS[path.id % COLORS.len()]);
+        for w in path.cells().windows(2) {
+            draw_line(&mut image, center(&w[0]), center(&w[1]), size / 5.0, color);
+        }
+    }
+
+    for (cell, glyph, color) in [
+        (&field.start_cell, &GLYPH_S, Color32::RED),
+        (&field.end_cell, &GLYPH_T, Color32::ORANGE),
+    ] {
+        if let Some(cell) = cell {
+            fill_circle(&mut image, center(cell), size / 2.0, rgba(color));
+            draw_glyph(&mut image, glyph, center(cell), size * 0.6);
+        }
+    }
+
+    Ok(image)
+}
+
+pub fn save_png(path: &str, field: &Field, cell_size: u32) -> Result<(), AppError> {
+    render_field(field, cell_size)?
+        .save_with_format(path, image::ImageFormat::Png)
+        .map_err(|_| AppError::FailedExport)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::field::{cell::CellType, path::Path};
+
+    use super::*;
+
+    #[test]
+    fn test_render_field() {
+        let mut field = Field::new();
+        field.width = 4;
+        field.height = 2;
+        field.filled_cells.insert(Cell::new(4, 2), CellType::Green);
+        field.paths = Some(vec![Path::new(vec![Cell::new(1, 2), Cell::new(3, 2)], 0)]);
+
+        let image = render_field(&field, 20).unwrap();
+
+        assert_eq!(image.dimensions(), (80, 40));
+        // Cell interiors, grid border and the path through the second row.
+        assert_eq!(image.get_pixel(10, 10), &rgba(Color32::LIGHT_GRAY));
+        assert_eq!(image.get_pixel(70, 30), &rgba(CellType::Green.color()));
+        assert_eq!(image.get_pixel(0, 0), &rgba(Color32::GRAY));
+        assert_eq!(image.get_pixel(30, 30), &rgba(COLORS[0]));
+    }
+}
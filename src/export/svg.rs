@@ -0,0 +1,136 @@
+use std::fmt::Write;
+
+use eframe::egui::Color32;
+
+use crate::{
+    consts::COLORS,
+    error::AppError,
+    field::{cell::Cell, Field},
+};
+
+fn hex(color: Color32) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+}
+
+/// Renders the grid, cell types, endpoints and paths of `field` as an SVG
+/// document where each cell is `cell_size` units wide.
+pub fn field_to_svg(field: &Field, cell_size: f32) -> String {
+    let width = field.width as f32 * cell_size;
+    let height = field.height as f32 * cell_size;
+    let center = |cell: &Cell| {
+        (
+            (cell.x as f32 - 0.5) * cell_size,
+            (cell.y as f32 - 0.5) * cell_size,
+        )
+    };
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    );
+
+    let _ = writeln!(
+        svg,
+        r#"<g stroke="{}" stroke-width="{}">"#,
+        hex(Color32::GRAY),
+        cell_size / 20.0
+    );
+    for y in 1..=field.height {
+        for x in 1..=field.width {
+            let color = match field.filled_cells.get(&Cell::new(x, y)) {
+                Some(cell_type) => cell_type.color(),
+                None => Color32::LIGHT_GRAY,
+            };
+            let _ = writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{cell_size}" height="{cell_size}" fill="{}"/>"#,
+                (x - 1) as f32 * cell_size,
+                (y - 1) as f32 * cell_size,
+                hex(color)
+            );
+        }
+    }
+    let _ = writeln!(svg, "</g>");
+
+    for path in field.paths.iter().flatten() {
+        let points = path
+            .cells()
+            .iter()
+            .map(|cell| {
+                let (x, y) = center(cell);
+                format!("{x},{y}")
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let _ = writeln!(
+            svg,
+            r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linejoin="round"/>"#,
+            points,
+            hex(COLORS[path.id % COLORS.len()]),
+            cell_size / 5.0
+        );
+    }
+
+    for (cell, label, color) in [
+        (&field.start_cell, "S", Color32::RED),
+        (&field.end_cell, "T", Color32::ORANGE),
+    ] {
+        if let Some(cell) = cell {
+            let (x, y) = center(cell);
+            let _ = writeln!(
+                svg,
+                r#"<circle cx="{x}" cy="{y}" r="{}" fill="{}"/>"#,
+                cell_size / 2.0,
+                hex(color)
+            );
+            let _ = writeln!(
+                svg,
+                r#"<text x="{x}" y="{y}" font-family="sans-serif" font-size="{}" text-anchor="middle" dominant-baseline="central" fill="black">{label}</text>"#,
+                cell_size * 0.7
+            );
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+pub fn save_svg(path: &str, field: &Field, cell_size: f32) -> Result<(), AppError> {
+    std::fs::write(path, field_to_svg(field, cell_size)).map_err(|_| AppError::FailedExport)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::field::{cell::CellType, path::Path};
+
+    use super::*;
+
+    #[test]
+    fn test_field_to_svg() {
+        let mut field = Field::new();
+        field.width = 3;
+        field.height = 2;
+        field.start_cell = Some(Cell::new(1, 1));
+        field.end_cell = Some(Cell::new(3, 1));
+        field.filled_cells.insert(Cell::new(2, 1), CellType::Green);
+        field.paths = Some(vec![Path::new(
+            vec![Cell::new(1, 1), Cell::new(2, 2), Cell::new(3, 1)],
+            1,
+        )]);
+
+        let svg = field_to_svg(&field, 10.0);
+
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="30" height="20""#)
+        );
+        assert_eq!(svg.matches("<rect ").count(), 6);
+        assert!(svg.contains(&format!(r#"fill="{}""#, hex(CellType::Green.color()))));
+        assert!(svg.contains(&format!(
+            r#"<polyline points="5,5 15,15 25,5" fill="none" stroke="{}""#,
+            hex(COLORS[1])
+        )));
+        assert_eq!(svg.matches("<circle ").count(), 2);
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+}
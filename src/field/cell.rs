@@ -1,6 +1,7 @@
 use eframe::egui::Color32;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialOrd, Ord, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialOrd, Ord, PartialEq, Serialize, Deserialize)]
 pub struct Cell {
     pub x: usize,
     pub y: usize,
@@ -12,7 +13,8 @@ impl Cell {
     }
 }
 
-#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum CellType {
     Green,
     Pink,
@@ -0,0 +1,217 @@
+use crate::field::cell::{Cell, CellType};
+
+/// A single reversible change to a [`super::Field`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Edit {
+    Fill(Cell, CellType),
+    Clear(Cell, CellType),
+    AddPinkPair(Cell, Cell),
+    RemovePinkPair(Cell, Cell),
+    MoveStart {
+        from: Option<Cell>,
+        to: Option<Cell>,
+    },
+    MoveEnd {
+        from: Option<Cell>,
+        to: Option<Cell>,
+    },
+    Resize {
+        from: (usize, usize),
+        to: (usize, usize),
+    },
+}
+
+impl Edit {
+    pub fn inverse(&self) -> Edit {
+        match *self {
+            Edit::Fill(cell, cell_type) => Edit::Clear(cell, cell_type),
+            Edit::Clear(cell, cell_type) => Edit::Fill(cell, cell_type),
+            Edit::AddPinkPair(a, b) => Edit::RemovePinkPair(a, b),
+            Edit::RemovePinkPair(a, b) => Edit::AddPinkPair(a, b),
+            Edit::MoveStart { from, to } => Edit::MoveStart { from: to, to: from },
+            Edit::MoveEnd { from, to } => Edit::MoveEnd { from: to, to: from },
+            Edit::Resize { from, to } => Edit::Resize { from: to, to: from },
+        }
+    }
+}
+
+/// Undo/redo stacks. Edits made during one pointer stroke are grouped so a
+/// whole drag is undone at once.
+#[derive(Default)]
+pub struct History {
+    stroke: Vec<Edit>,
+    undo_stack: Vec<Vec<Edit>>,
+    redo_stack: Vec<Vec<Edit>>,
+}
+
+impl History {
+    pub fn record(&mut self, edit: Edit) {
+        self.stroke.push(edit);
+    }
+
+    pub fn end_stroke(&mut self) {
+        if !self.stroke.is_empty() {
+            self.undo_stack.push(std::mem::take(&mut self.stroke));
+            self.redo_stack.clear();
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.stroke.is_empty() || !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.stroke.is_empty() && !self.redo_stack.is_empty()
+    }
+
+    /// Returns the edits to revert, most recent first.
+    pub fn undo(&mut self) -> Option<Vec<Edit>> {
+        self.end_stroke();
+        let edits = self.undo_stack.pop()?;
+        let inverse = edits.iter().rev().map(Edit::inverse).collect();
+        self.redo_stack.push(edits);
+        Some(inverse)
+    }
+
+    /// Returns the edits to apply again, in their original order.
+    pub fn redo(&mut self) -> Option<Vec<Edit>> {
+        self.end_stroke();
+        let edits = self.redo_stack.pop()?;
+        self.undo_stack.push(edits.clone());
+        Some(edits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::field::Field;
+
+    use super::*;
+
+    #[test]
+    fn test_undo_redo_stroke() {
+        let mut field = Field::new();
+        field.edit(Edit::Fill(Cell::new(1, 1), CellType::Green));
+        field.edit(Edit::Fill(Cell::new(2, 1), CellType::Green));
+        field.history.end_stroke();
+        field.edit(Edit::MoveStart {
+            from: None,
+            to: Some(Cell::new(3, 3)),
+        });
+        field.history.end_stroke();
+
+        field.undo();
+        assert_eq!(field.start_cell, None);
+        assert_eq!(field.filled_cells.len(), 2);
+
+        field.undo();
+        assert!(field.filled_cells.is_empty());
+        assert!(!field.can_undo());
+
+        field.redo();
+        field.redo();
+        assert_eq!(field.filled_cells.len(), 2);
+        assert_eq!(field.start_cell, Some(Cell::new(3, 3)));
+        assert!(!field.can_redo());
+    }
+
+    #[test]
+    fn test_pink_pair_undo() {
+        let mut field = Field::new();
+        field.edit(Edit::Fill(Cell::new(5, 4), CellType::Green));
+        field.edit(Edit::Fill(Cell::new(6, 5), CellType::Green));
+        field.history.end_stroke();
+
+        field.handle_add_pink_pair_constraint(Cell::new(5, 5));
+        field.history.end_stroke();
+        field.handle_remove_pink_pair_constraint(Cell::new(6, 4));
+        field.history.end_stroke();
+        assert!(field.pink_pair_map.is_empty());
+
+        field.undo();
+        assert_eq!(field.pink_pair_map.len(), 2);
+        assert!(field.is_pink_cell(&Cell::new(5, 5)));
+
+        field.undo();
+        assert!(field.pink_pair_map.is_empty());
+        assert!(!field.is_pink_cell(&Cell::new(5, 5)));
+    }
+
+    #[test]
+    fn test_new_edit_clears_redo() {
+        let mut field = Field::new();
+        field.edit(Edit::Fill(Cell::new(1, 1), CellType::Yellow));
+        field.undo();
+        assert!(field.can_redo());
+
+        field.edit(Edit::Fill(Cell::new(2, 2), CellType::Orange));
+        field.history.end_stroke();
+        assert!(!field.can_redo());
+    }
+
+    #[test]
+    fn test_resize_undo_restores_clipped_cells() {
+        let mut field = Field::new();
+        field.edit(Edit::Fill(Cell::new(30, 15), CellType::Orange));
+        field.edit(Edit::MoveEnd {
+            from: None,
+            to: Some(Cell::new(35, 2)),
+        });
+        field.history.end_stroke();
+
+        field.resize(20, 10);
+        field.history.end_stroke();
+        assert!(field.filled_cells.is_empty());
+        assert_eq!(field.end_cell, None);
+
+        field.undo();
+        assert_eq!((field.width, field.height), (40, 20));
+        assert_eq!(field.filled_cells.len(), 1);
+        assert_eq!(field.end_cell, Some(Cell::new(35, 2)));
+    }
+
+    #[test]
+    fn test_replace_layout_undo() {
+        let mut field = Field::new();
+        field.edit(Edit::Fill(Cell::new(30, 15), CellType::Orange));
+        field.history.end_stroke();
+
+        let mut other = Field::new();
+        other.width = 6;
+        other.height = 4;
+        other.start_cell = Some(Cell::new(1, 1));
+        other.filled_cells.insert(Cell::new(3, 2), CellType::Green);
+        other.set_cell_size(5.0);
+
+        field.replace_layout(&other);
+        assert_eq!((field.width, field.height), (6, 4));
+        assert_eq!(field.start_cell, Some(Cell::new(1, 1)));
+        assert_eq!(field.filled_cells, other.filled_cells);
+        assert_ne!(field.cell_size(), other.cell_size());
+
+        field.undo();
+        assert_eq!((field.width, field.height), (40, 20));
+        assert_eq!(field.start_cell, None);
+        assert_eq!(field.filled_cells.len(), 1);
+
+        field.undo();
+        assert!(field.filled_cells.is_empty());
+    }
+
+    #[test]
+    fn test_undo_clears_paths() {
+        let mut field = Field::new();
+        field.edit(Edit::Fill(Cell::new(1, 1), CellType::Green));
+        field.history.end_stroke();
+        field.paths = Some(Vec::new());
+        field.highlighted_cells.insert(Cell::new(1, 1));
+
+        field.undo();
+        assert!(field.paths.is_none());
+        assert!(field.highlighted_cells.is_empty());
+
+        field.paths = Some(Vec::new());
+        field.redo();
+        assert!(field.paths.is_none());
+    }
+}
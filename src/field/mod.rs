@@ -1,4 +1,5 @@
 pub mod cell;
+pub mod history;
 pub mod path;
 
 use std::collections::{BTreeMap, HashMap, HashSet};
@@ -8,6 +9,7 @@ use crate::{
     error::AppError,
     field::{
         cell::CellType,
+        history::{Edit, History},
         path::{parse_neos_output, Path},
     },
 };
@@ -16,6 +18,10 @@ use eframe::egui::{
     Align2, Color32, FontId, Painter, Pos2, Rect, Response, Sense, Stroke, Ui, Vec2,
 };
 
+pub const MAX_FIELD_SIZE: usize = 200;
+pub const MIN_CELL_SIZE: f32 = 4.0;
+pub const MAX_CELL_SIZE: f32 = 40.0;
+
 pub struct Field {
     pub width: usize,
     pub height: usize,
@@ -25,7 +31,10 @@ pub struct Field {
     pub start_cell: Option<Cell>,
     pub end_cell: Option<Cell>,
     pub paths: Option<Vec<Path>>,
+    /// Cells flagged by path validation, outlined on the canvas.
+    pub highlighted_cells: HashSet<Cell>,
     pub line_segment_start: Option<Pos2>,
+    history: History,
     response: Option<Response>,
     painter: Option<Painter>,
 }
@@ -41,7 +50,9 @@ impl Default for Field {
             start_cell: None,
             end_cell: None,
             paths: None,
+            highlighted_cells: HashSet::new(),
             line_segment_start: None,
+            history: History::default(),
             response: None,
             painter: None,
         }
@@ -104,6 +115,10 @@ impl Field {
         let mut path = Vec::new();
 
         while current_cell != end_cell {
+            // More steps than links means the links contain a cycle.
+            if path.len() > data.len() {
+                return Err(AppError::InvalidPath);
+            }
             path.push(current_cell);
             current_cell = *data.get(&current_cell).ok_or(AppError::InvalidPath)?;
         }
@@ -153,6 +168,8 @@ impl Field {
 
         self.draw_paths();
 
+        self.draw_highlighted_cells();
+
         self.draw_endpoint(&self.start_cell, "S", Color32::RED);
         self.draw_endpoint(&self.end_cell, "T", Color32::ORANGE);
 
@@ -203,6 +220,18 @@ impl Field {
         }
     }
 
+    fn draw_highlighted_cells(&self) {
+        for cell in self.highlighted_cells.iter().filter(|c| self.contains(c)) {
+            self.painter().rect(
+                self.cell_rect(cell),
+                0.0,
+                Color32::TRANSPARENT,
+                Stroke::new(3.0, Color32::RED),
+                eframe::egui::StrokeKind::Inside,
+            );
+        }
+    }
+
     fn draw_hovered_cell(&self) {
         if let Some(cell) = self.hovered_cell() {
             self.painter().rect(
@@ -303,17 +332,17 @@ impl Field {
         ) {
             let cells_touched_by_line = self.bresenham_cells(start_cell, end_cell);
             for cell in cells_touched_by_line {
-                if let std::collections::hash_map::Entry::Vacant(e) = self.filled_cells.entry(cell)
-                {
+                if !self.is_cell_occupied(&cell) {
                     if cell_type == CellType::Pink {
                         self.handle_add_pink_pair_constraint(cell);
                     } else {
-                        e.insert(cell_type);
+                        self.edit(Edit::Fill(cell, cell_type));
                     }
                 }
             }
         }
         self.line_segment_start = self.pointer_click_pos();
+        self.end_stroke_if_released();
     }
 
     pub fn handle_removing_cells(&mut self) {
@@ -327,32 +356,258 @@ impl Field {
                     if cell_type == CellType::Pink {
                         self.handle_remove_pink_pair_constraint(cell);
                     } else {
-                        self.filled_cells.remove(&cell);
+                        self.edit(Edit::Clear(cell, cell_type));
                     }
                 }
             }
         }
         self.line_segment_start = self.pointer_click_pos();
+        self.end_stroke_if_released();
     }
 
     pub fn handle_start_cell_selection(&mut self) {
         if let Some(cell) = self.clicked_cell() {
-            if !self.is_cell_occupied(&cell) {
-                self.start_cell = Some(cell)
+            if !self.is_cell_occupied(&cell) && self.start_cell != Some(cell) {
+                self.edit(Edit::MoveStart {
+                    from: self.start_cell,
+                    to: Some(cell),
+                });
             }
         }
+        self.end_stroke_if_released();
     }
 
     pub fn handle_end_cell_selection(&mut self) {
         if let Some(cell) = self.clicked_cell() {
-            if !self.is_cell_occupied(&cell) {
-                self.end_cell = Some(cell)
+            if !self.is_cell_occupied(&cell) && self.end_cell != Some(cell) {
+                self.edit(Edit::MoveEnd {
+                    from: self.end_cell,
+                    to: Some(cell),
+                });
+            }
+        }
+        self.end_stroke_if_released();
+    }
+
+    fn end_stroke_if_released(&mut self) {
+        if self.pointer_click_pos().is_none() {
+            self.history.end_stroke();
+        }
+    }
+
+    fn apply(&mut self, edit: Edit) {
+        match edit {
+            Edit::Fill(cell, cell_type) => {
+                self.filled_cells.insert(cell, cell_type);
+            }
+            Edit::Clear(cell, _) => {
+                self.filled_cells.remove(&cell);
+            }
+            Edit::AddPinkPair(a, b) => {
+                self.filled_cells.insert(a, CellType::Pink);
+                self.filled_cells.insert(b, CellType::Pink);
+
+                self.pink_pair_map.insert(a, b);
+                self.pink_pair_map.insert(b, a);
+            }
+            Edit::RemovePinkPair(a, b) => {
+                self.pink_pair_map.remove(&a);
+                self.pink_pair_map.remove(&b);
+
+                self.filled_cells.remove(&a);
+                self.filled_cells.remove(&b);
+            }
+            Edit::MoveStart { to, .. } => self.start_cell = to,
+            Edit::MoveEnd { to, .. } => self.end_cell = to,
+            Edit::Resize {
+                to: (width, height),
+                ..
+            } => {
+                self.width = width;
+                self.height = height;
+            }
+        }
+    }
+
+    /// Applies `edit` and records it in the undo history.
+    fn edit(&mut self, edit: Edit) {
+        self.apply(edit);
+        self.history.record(edit);
+    }
+
+    /// Changes the grid dimensions, dropping cells, pink pairs and endpoints
+    /// that no longer fit. The whole resize is a single undoable step.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        if (width, height) == (self.width, self.height) {
+            return;
+        }
+
+        let fits = |cell: &Cell| cell.x <= width && cell.y <= height;
+
+        let clipped_pairs = self
+            .unique_pink_pairs()
+            .into_iter()
+            .filter(|(a, b)| !fits(a) || !fits(b))
+            .map(|(a, b)| (*a, *b))
+            .collect::<Vec<_>>();
+        for (a, b) in clipped_pairs {
+            self.edit(Edit::RemovePinkPair(a, b));
+        }
+
+        let clipped_cells = self
+            .filled_cells
+            .iter()
+            .filter(|(cell, _)| !fits(cell))
+            .map(|(cell, cell_type)| (*cell, *cell_type))
+            .collect::<Vec<_>>();
+        for (cell, cell_type) in clipped_cells {
+            self.edit(Edit::Clear(cell, cell_type));
+        }
+
+        if self.start_cell.is_some_and(|cell| !fits(&cell)) {
+            self.edit(Edit::MoveStart {
+                from: self.start_cell,
+                to: None,
+            });
+        }
+
+        if self.end_cell.is_some_and(|cell| !fits(&cell)) {
+            self.edit(Edit::MoveEnd {
+                from: self.end_cell,
+                to: None,
+            });
+        }
+
+        self.edit(Edit::Resize {
+            from: (self.width, self.height),
+            to: (width, height),
+        });
+        self.clear_paths();
+    }
+
+    /// Replaces every filled cell and pink pair as a single undoable step.
+    /// Pink cells in `filled_cells` are only kept if they are part of a pair.
+    pub fn replace_cells(
+        &mut self,
+        filled_cells: HashMap<Cell, CellType>,
+        pink_pairs: &[(Cell, Cell)],
+    ) {
+        self.history.end_stroke();
+        self.clear_cells();
+        self.fill_cells(filled_cells, pink_pairs);
+        self.history.end_stroke();
+        self.clear_paths();
+    }
+
+    /// Takes over the dimensions, endpoints, cells and pink pairs of `other`
+    /// as a single undoable step. The cell size and history stay.
+    pub fn replace_layout(&mut self, other: &Field) {
+        self.history.end_stroke();
+        self.clear_cells();
+
+        if (other.width, other.height) != (self.width, self.height) {
+            self.edit(Edit::Resize {
+                from: (self.width, self.height),
+                to: (other.width, other.height),
+            });
+        }
+
+        if other.start_cell != self.start_cell {
+            self.edit(Edit::MoveStart {
+                from: self.start_cell,
+                to: other.start_cell,
+            });
+        }
+
+        if other.end_cell != self.end_cell {
+            self.edit(Edit::MoveEnd {
+                from: self.end_cell,
+                to: other.end_cell,
+            });
+        }
+
+        let pink_pairs = other
+            .unique_pink_pairs()
+            .into_iter()
+            .map(|(a, b)| (*a, *b))
+            .collect::<Vec<_>>();
+        self.fill_cells(other.filled_cells.clone(), &pink_pairs);
+
+        self.history.end_stroke();
+        self.clear_paths();
+    }
+
+    fn clear_cells(&mut self) {
+        let old_pairs = self
+            .unique_pink_pairs()
+            .into_iter()
+            .map(|(a, b)| (*a, *b))
+            .collect::<Vec<_>>();
+        for (a, b) in old_pairs {
+            self.edit(Edit::RemovePinkPair(a, b));
+        }
+
+        let old_cells = self
+            .filled_cells
+            .iter()
+            .map(|(cell, cell_type)| (*cell, *cell_type))
+            .collect::<Vec<_>>();
+        for (cell, cell_type) in old_cells {
+            self.edit(Edit::Clear(cell, cell_type));
+        }
+    }
+
+    fn fill_cells(&mut self, filled_cells: HashMap<Cell, CellType>, pink_pairs: &[(Cell, Cell)]) {
+        for (cell, cell_type) in filled_cells {
+            if cell_type != CellType::Pink {
+                self.edit(Edit::Fill(cell, cell_type));
+            }
+        }
+
+        for (a, b) in pink_pairs {
+            self.edit(Edit::AddPinkPair(*a, *b));
+        }
+    }
+
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    pub fn set_cell_size(&mut self, cell_size: f32) {
+        self.cell_size = cell_size.clamp(MIN_CELL_SIZE, MAX_CELL_SIZE);
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.history.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.history.can_redo()
+    }
+
+    /// Reverts the last step. Paths found for the edited field are dropped,
+    /// as after any other edit.
+    pub fn undo(&mut self) {
+        if let Some(edits) = self.history.undo() {
+            for edit in edits {
+                self.apply(edit);
+            }
+            self.clear_paths();
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if let Some(edits) = self.history.redo() {
+            for edit in edits {
+                self.apply(edit);
             }
+            self.clear_paths();
         }
     }
 
     pub fn clear_paths(&mut self) {
         self.paths = None;
+        self.highlighted_cells.clear();
     }
 
     pub fn is_cell_occupied(&self, cell: &Cell) -> bool {
@@ -453,21 +708,14 @@ impl Field {
 
     pub fn handle_add_pink_pair_constraint(&mut self, cell: Cell) {
         if let Some((c1, c2)) = self.find_pink_diagonal_match(&cell) {
-            self.filled_cells.insert(c1, CellType::Pink);
-            self.filled_cells.insert(c2, CellType::Pink);
-
-            self.pink_pair_map.insert(c1, c2);
-            self.pink_pair_map.insert(c2, c1);
+            self.edit(Edit::AddPinkPair(c1, c2));
         }
     }
 
     pub fn handle_remove_pink_pair_constraint(&mut self, cell: Cell) {
         if self.is_pink_cell(&cell) {
-            if let Some(pair) = self.pink_pair_map.remove(&cell) {
-                self.pink_pair_map.remove(&pair);
-
-                self.filled_cells.remove(&cell);
-                self.filled_cells.remove(&pair);
+            if let Some(&pair) = self.pink_pair_map.get(&cell) {
+                self.edit(Edit::RemovePinkPair(cell, pair));
             }
         }
     }
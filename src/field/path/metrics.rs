@@ -0,0 +1,111 @@
+use std::ops::Add;
+
+use crate::{
+    field::{cell::CellType, path::Path, Field},
+    local_solver::{DIAGONAL_MOVE_COST, STRAIGHT_MOVE_COST},
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PathMetrics {
+    pub steps: usize,
+    pub euclidean_length: f64,
+    /// Cost with the 1 / 1.4 move weights used by the templates.
+    pub weighted_cost: f64,
+    pub turns: usize,
+    pub yellow_cells: usize,
+    pub orange_cells: usize,
+}
+
+impl PathMetrics {
+    pub fn compute(path: &Path, field: &Field) -> Self {
+        let cells = path.cells();
+
+        let moves = cells
+            .windows(2)
+            .map(|w| (w[1].x as i64 - w[0].x as i64, w[1].y as i64 - w[0].y as i64))
+            .collect::<Vec<_>>();
+
+        let (euclidean_length, weighted_cost) =
+            moves.iter().fold((0.0, 0.0), |(length, cost), (dx, dy)| {
+                if *dx != 0 && *dy != 0 {
+                    (length + std::f64::consts::SQRT_2, cost + DIAGONAL_MOVE_COST)
+                } else {
+                    (length + 1.0, cost + STRAIGHT_MOVE_COST)
+                }
+            });
+
+        let count_cells = |cell_type: CellType| {
+            cells
+                .iter()
+                .filter(|cell| field.filled_cells.get(cell) == Some(&cell_type))
+                .count()
+        };
+
+        Self {
+            steps: moves.len(),
+            euclidean_length,
+            weighted_cost,
+            turns: moves.windows(2).filter(|w| w[0] != w[1]).count(),
+            yellow_cells: count_cells(CellType::Yellow),
+            orange_cells: count_cells(CellType::Orange),
+        }
+    }
+}
+
+impl Add for PathMetrics {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            steps: self.steps + other.steps,
+            euclidean_length: self.euclidean_length + other.euclidean_length,
+            weighted_cost: self.weighted_cost + other.weighted_cost,
+            turns: self.turns + other.turns,
+            yellow_cells: self.yellow_cells + other.yellow_cells,
+            orange_cells: self.orange_cells + other.orange_cells,
+        }
+    }
+}
+
+impl std::iter::Sum for PathMetrics {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::field::cell::Cell;
+
+    use super::*;
+
+    #[test]
+    fn test_compute() {
+        let mut field = Field::new();
+        field.filled_cells.insert(Cell::new(2, 1), CellType::Yellow);
+        field.filled_cells.insert(Cell::new(3, 2), CellType::Orange);
+
+        // right, diagonal, down, down
+        let path = Path::new(
+            vec![
+                Cell::new(1, 1),
+                Cell::new(2, 1),
+                Cell::new(3, 2),
+                Cell::new(3, 3),
+                Cell::new(3, 4),
+            ],
+            0,
+        );
+        let metrics = PathMetrics::compute(&path, &field);
+
+        assert_eq!(metrics.steps, 4);
+        assert_eq!(metrics.turns, 2);
+        assert_eq!(metrics.yellow_cells, 1);
+        assert_eq!(metrics.orange_cells, 1);
+        assert!((metrics.weighted_cost - 4.4).abs() < 1e-9);
+        assert!((metrics.euclidean_length - (3.0 + std::f64::consts::SQRT_2)).abs() < 1e-9);
+
+        let total: PathMetrics = [metrics, metrics].into_iter().sum();
+        assert_eq!(total.steps, 8);
+    }
+}
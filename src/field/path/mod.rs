@@ -1,4 +1,6 @@
+pub mod metrics;
 pub mod parser;
+pub mod validation;
 
 use crate::field::cell::Cell;
 
@@ -0,0 +1,367 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{
+    field::{
+        cell::{Cell, CellType},
+        path::Path,
+        Field,
+    },
+    local_solver::Neighborhood,
+    template::{param::ParamValues, Template},
+};
+
+/// Constraints a template imposes on every path it produces.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Rules {
+    pub neighborhood: Neighborhood,
+    pub avoid_green: bool,
+    pub no_corner_cutting: bool,
+    pub pink_pairs: bool,
+    pub max_yellow_nodes: Option<usize>,
+    pub max_orange_nodes: Option<usize>,
+    /// Paths may only share the start and terminal.
+    pub disjoint: bool,
+}
+
+impl Rules {
+    /// Custom templates have no known constraints and yield `None`.
+    pub fn for_template(template: &Template, params: &ParamValues) -> Option<Self> {
+        let mut rules = Self {
+            neighborhood: Neighborhood::Eight,
+            avoid_green: true,
+            no_corner_cutting: false,
+            pink_pairs: false,
+            max_yellow_nodes: None,
+            max_orange_nodes: None,
+            disjoint: false,
+        };
+
+        match template {
+            Template::Default => {
+                rules.neighborhood = Neighborhood::Four;
+                rules.avoid_green = false;
+            }
+            Template::Disabled => rules.neighborhood = Neighborhood::Four,
+            Template::Eight | Template::TurnCost => {}
+            Template::Multiple | Template::MultipleSections | Template::MultipleSeparated => {
+                rules.disjoint = true;
+            }
+            Template::CornerCutting => {
+                rules.no_corner_cutting = true;
+                rules.disjoint = true;
+            }
+            Template::Pink => rules.pink_pairs = true,
+            Template::Full => {
+                let limit = |name| {
+                    template
+                        .int_param(params, name)
+                        .map(|value| value.max(0) as usize)
+                };
+
+                rules.pink_pairs = true;
+                rules.max_yellow_nodes = limit("max_yellow_nodes");
+                rules.max_orange_nodes = limit("max_orange_nodes");
+                rules.disjoint = true;
+            }
+            Template::Custom(_) => return None,
+        }
+
+        Some(rules)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Violation {
+    OutOfBounds(Cell),
+    NotAdjacent(Cell, Cell),
+    DiagonalMove(Cell, Cell),
+    CornerCut(Cell, Cell),
+    GreenCell(Cell),
+    PinkPair(Cell, Cell),
+    YellowLimit {
+        cells: Vec<Cell>,
+        max: usize,
+    },
+    OrangeLimit {
+        cells: Vec<Cell>,
+        max: usize,
+    },
+    /// The cell is also used by the path with the given id.
+    SharedCell {
+        cell: Cell,
+        other_path: usize,
+    },
+}
+
+impl Violation {
+    /// Cells to highlight on the canvas.
+    pub fn cells(&self) -> Vec<Cell> {
+        match self {
+            Violation::OutOfBounds(cell)
+            | Violation::GreenCell(cell)
+            | Violation::SharedCell { cell, .. } => vec![*cell],
+            Violation::NotAdjacent(a, b)
+            | Violation::DiagonalMove(a, b)
+            | Violation::CornerCut(a, b)
+            | Violation::PinkPair(a, b) => vec![*a, *b],
+            Violation::YellowLimit { cells, .. } | Violation::OrangeLimit { cells, .. } => {
+                cells.clone()
+            }
+        }
+    }
+}
+
+fn format_cell(cell: &Cell) -> String {
+    format!("({},{})", cell.x, cell.y)
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::OutOfBounds(cell) => write!(f, "{} is outside the field", format_cell(cell)),
+            Violation::NotAdjacent(a, b) => write!(
+                f,
+                "{} -> {} is not a move to a neighbouring cell",
+                format_cell(a),
+                format_cell(b)
+            ),
+            Violation::DiagonalMove(a, b) => write!(
+                f,
+                "{} -> {} is diagonal, the template only allows straight moves",
+                format_cell(a),
+                format_cell(b)
+            ),
+            Violation::CornerCut(a, b) => write!(
+                f,
+                "{} -> {} cuts the corner of a green cell",
+                format_cell(a),
+                format_cell(b)
+            ),
+            Violation::GreenCell(cell) => write!(f, "{} is a green cell", format_cell(cell)),
+            Violation::PinkPair(a, b) => write!(
+                f,
+                "both cells of pink pair {} / {} are visited",
+                format_cell(a),
+                format_cell(b)
+            ),
+            Violation::YellowLimit { cells, max } => {
+                write!(
+                    f,
+                    "{} yellow cells visited, at most {} allowed",
+                    cells.len(),
+                    max
+                )
+            }
+            Violation::OrangeLimit { cells, max } => {
+                write!(
+                    f,
+                    "{} orange cells visited, at most {} allowed",
+                    cells.len(),
+                    max
+                )
+            }
+            Violation::SharedCell { cell, other_path } => write!(
+                f,
+                "{} is also used by path {}",
+                format_cell(cell),
+                other_path + 1
+            ),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ValidationReport {
+    pub path_id: usize,
+    pub violations: Vec<Violation>,
+}
+
+fn validate_path(path: &Path, field: &Field, rules: &Rules) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let cells = path.cells();
+
+    for cell in cells {
+        if !field.contains(cell) {
+            violations.push(Violation::OutOfBounds(*cell));
+        } else if rules.avoid_green && field.is_green_cell(cell) {
+            violations.push(Violation::GreenCell(*cell));
+        }
+    }
+
+    for w in cells.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        let dx = a.x.abs_diff(b.x);
+        let dy = a.y.abs_diff(b.y);
+
+        if dx > 1 || dy > 1 || (dx == 0 && dy == 0) {
+            violations.push(Violation::NotAdjacent(a, b));
+        } else if dx == 1 && dy == 1 {
+            if rules.neighborhood == Neighborhood::Four {
+                violations.push(Violation::DiagonalMove(a, b));
+            } else if rules.no_corner_cutting
+                && (field.is_green_cell(&Cell::new(a.x, b.y))
+                    || field.is_green_cell(&Cell::new(b.x, a.y)))
+            {
+                violations.push(Violation::CornerCut(a, b));
+            }
+        }
+    }
+
+    if rules.pink_pairs {
+        for (a, b) in field.unique_pink_pairs() {
+            if cells.contains(a) && cells.contains(b) {
+                violations.push(Violation::PinkPair(*a, *b));
+            }
+        }
+    }
+
+    let cells_of_type = |cell_type: CellType| {
+        cells
+            .iter()
+            .filter(|cell| field.filled_cells.get(cell) == Some(&cell_type))
+            .copied()
+            .collect::<Vec<_>>()
+    };
+
+    if let Some(max) = rules.max_yellow_nodes {
+        let cells = cells_of_type(CellType::Yellow);
+        if cells.len() > max {
+            violations.push(Violation::YellowLimit { cells, max });
+        }
+    }
+
+    if let Some(max) = rules.max_orange_nodes {
+        let cells = cells_of_type(CellType::Orange);
+        if cells.len() > max {
+            violations.push(Violation::OrangeLimit { cells, max });
+        }
+    }
+
+    violations
+}
+
+/// Checks every path against the constraints of `template`. Only paths with
+/// at least one violation are reported.
+pub fn validate_paths(
+    paths: &[Path],
+    field: &Field,
+    template: &Template,
+    params: &ParamValues,
+) -> Vec<ValidationReport> {
+    let Some(rules) = Rules::for_template(template, params) else {
+        return Vec::new();
+    };
+    let endpoints = [field.start_cell, field.end_cell];
+
+    let mut owners: HashMap<Cell, usize> = HashMap::new();
+    let mut reports = Vec::new();
+
+    for path in paths {
+        let mut violations = validate_path(path, field, &rules);
+
+        if rules.disjoint {
+            for cell in path.cells() {
+                if endpoints.contains(&Some(*cell)) {
+                    continue;
+                }
+                match owners.get(cell) {
+                    Some(&other_path) if other_path != path.id => {
+                        violations.push(Violation::SharedCell {
+                            cell: *cell,
+                            other_path,
+                        });
+                    }
+                    _ => {
+                        owners.insert(*cell, path.id);
+                    }
+                }
+            }
+        }
+
+        if !violations.is_empty() {
+            reports.push(ValidationReport {
+                path_id: path.id,
+                violations,
+            });
+        }
+    }
+
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::param::ParamValue;
+
+    fn field() -> Field {
+        let mut field = Field::new();
+        field.start_cell = Some(Cell::new(1, 1));
+        field.end_cell = Some(Cell::new(3, 3));
+        field.filled_cells.insert(Cell::new(2, 1), CellType::Green);
+        field.filled_cells.insert(Cell::new(1, 2), CellType::Yellow);
+        field.filled_cells.insert(Cell::new(2, 3), CellType::Yellow);
+        field
+    }
+
+    fn path(cells: &[(usize, usize)], id: usize) -> Path {
+        Path::new(cells.iter().map(|&(x, y)| Cell::new(x, y)).collect(), id)
+    }
+
+    #[test]
+    fn test_valid_path() {
+        let paths = [path(&[(1, 1), (2, 2), (3, 3)], 0)];
+        assert!(validate_paths(&paths, &field(), &Template::Eight, &ParamValues::new()).is_empty());
+    }
+
+    #[test]
+    fn test_step_violations() {
+        let paths = [path(&[(1, 1), (2, 1), (3, 3)], 0)];
+        let reports = validate_paths(&paths, &field(), &Template::Eight, &ParamValues::new());
+
+        assert_eq!(
+            reports[0].violations,
+            vec![
+                Violation::GreenCell(Cell::new(2, 1)),
+                Violation::NotAdjacent(Cell::new(2, 1), Cell::new(3, 3)),
+            ]
+        );
+
+        let paths = [path(&[(1, 1), (2, 2), (3, 3)], 0)];
+        let reports = validate_paths(&paths, &field(), &Template::Disabled, &ParamValues::new());
+        assert_eq!(reports[0].violations.len(), 2);
+
+        let reports = validate_paths(
+            &paths,
+            &field(),
+            &Template::CornerCutting,
+            &ParamValues::new(),
+        );
+        assert_eq!(
+            reports[0].violations,
+            vec![Violation::CornerCut(Cell::new(1, 1), Cell::new(2, 2))]
+        );
+    }
+
+    #[test]
+    fn test_limits_and_disjointness() {
+        let params = ParamValues::from([("max_yellow_nodes".to_string(), ParamValue::Int(1))]);
+        let paths = [
+            path(&[(1, 1), (1, 2), (2, 3), (3, 3)], 0),
+            path(&[(1, 1), (2, 2), (2, 3), (3, 3)], 1),
+        ];
+        let reports = validate_paths(&paths, &field(), &Template::Full, &params);
+
+        assert!(matches!(
+            reports[0].violations[..],
+            [Violation::YellowLimit { max: 1, .. }]
+        ));
+        assert_eq!(
+            reports[1].violations,
+            vec![Violation::SharedCell {
+                cell: Cell::new(2, 3),
+                other_path: 0
+            }]
+        );
+    }
+}
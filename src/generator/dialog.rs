@@ -0,0 +1,129 @@
+use eframe::egui::{ComboBox, DragValue, Id, Modal, Ui};
+
+use super::{random_seed, GeneratorConfig, Strategy, MAX_SEED};
+
+pub struct GeneratorDialog {
+    pub config: GeneratorConfig,
+    open: bool,
+}
+
+impl GeneratorDialog {
+    pub fn new() -> Self {
+        Self {
+            config: GeneratorConfig::default(),
+            open: false,
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn open(&mut self) {
+        self.open = true;
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// Returns the config to generate with once the user confirms.
+    pub fn show(&mut self, ui: &mut Ui) -> Option<GeneratorConfig> {
+        let mut result = None;
+
+        Modal::new(Id::new("GENERATOR MODAL")).show(ui.ctx(), |ui| {
+            ui.set_width(250.0);
+
+            ui.heading("Generate interferences");
+
+            let config = &mut self.config;
+
+            ComboBox::from_label("Strategy")
+                .selected_text(config.strategy.name())
+                .show_ui(ui, |ui| {
+                    for variant in Strategy::variants() {
+                        if ui
+                            .selectable_label(
+                                config.strategy.name() == variant.name(),
+                                variant.name(),
+                            )
+                            .clicked()
+                        {
+                            config.strategy = *variant;
+                        }
+                    }
+                });
+
+            match &mut config.strategy {
+                Strategy::Uniform { density } => {
+                    ui.add(
+                        DragValue::new(density)
+                            .range(0.0..=1.0)
+                            .speed(0.01)
+                            .suffix(" Density"),
+                    );
+                }
+                Strategy::Clusters { count, radius } => {
+                    ui.add(DragValue::new(count).range(0..=100).suffix(" Clusters"));
+                    ui.add(DragValue::new(radius).range(0..=20).suffix(" Radius"));
+                }
+                Strategy::Walls { count, gap } => {
+                    ui.add(DragValue::new(count).range(0..=50).suffix(" Walls"));
+                    ui.add(DragValue::new(gap).range(1..=20).suffix(" Gap"));
+                }
+            }
+
+            ui.add(
+                DragValue::new(&mut config.yellow_share)
+                    .range(0.0..=1.0)
+                    .speed(0.01)
+                    .suffix(" Yellow share"),
+            );
+            ui.add(
+                DragValue::new(&mut config.orange_share)
+                    .range(0.0..=1.0)
+                    .speed(0.01)
+                    .suffix(" Orange share"),
+            );
+            ui.add(
+                DragValue::new(&mut config.pink_pairs)
+                    .range(0..=50)
+                    .suffix(" Pink pairs"),
+            );
+
+            ui.horizontal(|ui| {
+                ui.add(
+                    DragValue::new(&mut config.seed)
+                        .range(0..=MAX_SEED)
+                        .prefix("Seed "),
+                );
+                if ui.button("Random").clicked() {
+                    config.seed = random_seed();
+                }
+            });
+
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                if ui.button("Generate").clicked() {
+                    result = Some(*config);
+                    self.open = false;
+                }
+
+                ui.add_space(10.0);
+
+                if ui.button("Cancel").clicked() {
+                    self.open = false;
+                }
+            });
+        });
+
+        result
+    }
+}
+
+impl Default for GeneratorDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
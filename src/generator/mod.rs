@@ -0,0 +1,338 @@
+pub mod dialog;
+
+use std::collections::{HashMap, HashSet};
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use crate::{
+    error::AppError,
+    field::{
+        cell::{Cell, CellType},
+        Field,
+    },
+    local_solver::{self, Neighborhood, SearchOptions, DIAGONAL_MOVE_COST},
+};
+
+/// Largest seed offered by the dialog. The seed field edits an `f64`, which
+/// holds every integer up to 2^53 exactly.
+pub const MAX_SEED: u64 = (1 << 53) - 1;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Strategy {
+    /// Every cell becomes an obstacle with probability `density`.
+    Uniform { density: f64 },
+    /// Round blobs of obstacles around random centres.
+    Clusters { count: usize, radius: usize },
+    /// Full-length horizontal or vertical walls, each with one opening.
+    Walls { count: usize, gap: usize },
+}
+
+impl Strategy {
+    pub fn variants() -> &'static [Strategy] {
+        &[
+            Strategy::Uniform { density: 0.2 },
+            Strategy::Clusters {
+                count: 6,
+                radius: 3,
+            },
+            Strategy::Walls { count: 3, gap: 2 },
+        ]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Strategy::Uniform { .. } => "Uniform",
+            Strategy::Clusters { .. } => "Clusters",
+            Strategy::Walls { .. } => "Walls",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeneratorConfig {
+    pub strategy: Strategy,
+    pub seed: u64,
+    /// Share of obstacle cells turned yellow instead of green.
+    pub yellow_share: f64,
+    /// Share of obstacle cells turned orange instead of green.
+    pub orange_share: f64,
+    pub pink_pairs: usize,
+}
+
+impl Default for GeneratorConfig {
+    fn default() -> Self {
+        Self {
+            strategy: Strategy::variants()[0],
+            seed: 0,
+            yellow_share: 0.1,
+            orange_share: 0.1,
+            pink_pairs: 0,
+        }
+    }
+}
+
+/// Random seed the seed field can show and reproduce.
+pub fn random_seed() -> u64 {
+    rand::thread_rng().gen_range(0..=MAX_SEED)
+}
+
+/// Interference layout produced by [`generate`].
+pub struct Layout {
+    pub filled_cells: HashMap<Cell, CellType>,
+    pub pink_pairs: Vec<(Cell, Cell)>,
+}
+
+/// Builds a random layout for `field`'s size and endpoints. The same config
+/// always yields the same layout, and the terminal is always reachable from
+/// the start using horizontal and vertical moves only.
+pub fn generate(field: &Field, config: &GeneratorConfig) -> Result<Layout, AppError> {
+    let start = field.start_cell.ok_or(AppError::StartNotSet)?;
+    let end = field.end_cell.ok_or(AppError::EndNotSet)?;
+
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut obstacles = obstacle_cells(field, &config.strategy, &mut rng);
+    obstacles.remove(&start);
+    obstacles.remove(&end);
+
+    // Sort to keep the RNG consumption independent of the hash order.
+    let mut obstacles = obstacles.into_iter().collect::<Vec<_>>();
+    obstacles.sort();
+
+    let mut scratch = Field::new();
+    scratch.width = field.width;
+    scratch.height = field.height;
+    scratch.start_cell = Some(start);
+    scratch.end_cell = Some(end);
+
+    for cell in obstacles {
+        let roll: f64 = rng.gen();
+        let cell_type = if roll < config.yellow_share {
+            CellType::Yellow
+        } else if roll < config.yellow_share + config.orange_share {
+            CellType::Orange
+        } else {
+            CellType::Green
+        };
+        scratch.filled_cells.insert(cell, cell_type);
+    }
+
+    let options = SearchOptions {
+        neighborhood: Neighborhood::Four,
+        diagonal_cost: DIAGONAL_MOVE_COST,
+        avoid_green: true,
+    };
+    if local_solver::shortest_path(&scratch, &options).is_err() {
+        carve_corridor(&mut scratch, start, end, &mut rng);
+    }
+
+    let pink_pairs = place_pink_pairs(&mut scratch, config.pink_pairs, &mut rng);
+
+    Ok(Layout {
+        filled_cells: scratch.filled_cells,
+        pink_pairs,
+    })
+}
+
+fn random_cell(field: &Field, rng: &mut StdRng) -> Cell {
+    Cell::new(
+        rng.gen_range(1..=field.width),
+        rng.gen_range(1..=field.height),
+    )
+}
+
+fn obstacle_cells(field: &Field, strategy: &Strategy, rng: &mut StdRng) -> HashSet<Cell> {
+    let mut cells = HashSet::new();
+
+    match *strategy {
+        Strategy::Uniform { density } => {
+            for y in 1..=field.height {
+                for x in 1..=field.width {
+                    if rng.gen_bool(density.clamp(0.0, 1.0)) {
+                        cells.insert(Cell::new(x, y));
+                    }
+                }
+            }
+        }
+        Strategy::Clusters { count, radius } => {
+            // A radius of width + height already covers the whole field.
+            let r = radius.min(field.width + field.height) as i64;
+            for _ in 0..count.min(field.width * field.height) {
+                let centre = random_cell(field, rng);
+                for dy in -r..=r {
+                    for dx in -r..=r {
+                        let (x, y) = (centre.x as i64 + dx, centre.y as i64 + dy);
+                        let cell = Cell::new(x.max(0) as usize, y.max(0) as usize);
+                        if dx * dx + dy * dy <= r * r && x >= 1 && y >= 1 && field.contains(&cell) {
+                            cells.insert(cell);
+                        }
+                    }
+                }
+            }
+        }
+        Strategy::Walls { count, gap } => {
+            for _ in 0..count.min(field.width + field.height) {
+                let vertical = rng.gen_bool(0.5);
+                let (length, across) = if vertical {
+                    (field.height, field.width)
+                } else {
+                    (field.width, field.height)
+                };
+
+                let position = rng.gen_range(1..=across);
+                let gap = gap.min(length);
+                let gap_start = rng.gen_range(1..=length - gap + 1);
+
+                for i in (1..=length).filter(|i| !(gap_start..gap_start + gap).contains(i)) {
+                    cells.insert(if vertical {
+                        Cell::new(position, i)
+                    } else {
+                        Cell::new(i, position)
+                    });
+                }
+            }
+        }
+    }
+
+    cells
+}
+
+/// Clears green cells along a random monotone staircase from `start` to `end`.
+fn carve_corridor(field: &mut Field, start: Cell, end: Cell, rng: &mut StdRng) {
+    let mut cell = start;
+
+    while cell != end {
+        let can_move_x = cell.x != end.x;
+        let can_move_y = cell.y != end.y;
+
+        if can_move_x && (!can_move_y || rng.gen_bool(0.5)) {
+            cell.x = if end.x > cell.x {
+                cell.x + 1
+            } else {
+                cell.x - 1
+            };
+        } else {
+            cell.y = if end.y > cell.y {
+                cell.y + 1
+            } else {
+                cell.y - 1
+            };
+        }
+
+        if field.is_green_cell(&cell) {
+            field.filled_cells.remove(&cell);
+        }
+    }
+}
+
+fn place_pink_pairs(field: &mut Field, count: usize, rng: &mut StdRng) -> Vec<(Cell, Cell)> {
+    let mut pairs = Vec::new();
+    let count = count.min(field.width * field.height / 2);
+    let max_attempts = 100 * count;
+
+    for _ in 0..max_attempts {
+        if pairs.len() == count {
+            break;
+        }
+
+        let cell = random_cell(field, rng);
+        if let Some((a, b)) = field.find_pink_diagonal_match(&cell) {
+            if [a, b]
+                .iter()
+                .any(|c| Some(*c) == field.start_cell || Some(*c) == field.end_cell)
+            {
+                continue;
+            }
+            field.filled_cells.insert(a, CellType::Pink);
+            field.filled_cells.insert(b, CellType::Pink);
+            pairs.push((a, b));
+        }
+    }
+
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field() -> Field {
+        let mut field = Field::new();
+        field.start_cell = Some(Cell::new(1, 1));
+        field.end_cell = Some(Cell::new(40, 20));
+        field
+    }
+
+    #[test]
+    fn test_same_seed_same_layout() {
+        let config = GeneratorConfig {
+            seed: 42,
+            pink_pairs: 3,
+            ..Default::default()
+        };
+
+        let a = generate(&field(), &config).unwrap();
+        let b = generate(&field(), &config).unwrap();
+        assert_eq!(a.filled_cells, b.filled_cells);
+        assert_eq!(a.pink_pairs, b.pink_pairs);
+    }
+
+    #[test]
+    fn test_endpoints_stay_connected() {
+        let field = field();
+
+        for seed in 0..20 {
+            for strategy in [
+                Strategy::Uniform { density: 0.6 },
+                Strategy::Clusters {
+                    count: 20,
+                    radius: 4,
+                },
+                Strategy::Walls { count: 10, gap: 1 },
+            ] {
+                let config = GeneratorConfig {
+                    strategy,
+                    seed,
+                    yellow_share: 0.0,
+                    orange_share: 0.0,
+                    pink_pairs: 0,
+                };
+                let layout = generate(&field, &config).unwrap();
+
+                let mut generated = Field::new();
+                generated.start_cell = field.start_cell;
+                generated.end_cell = field.end_cell;
+                generated.filled_cells = layout.filled_cells;
+
+                assert!(!generated.is_cell_occupied(&Cell::new(1, 1)));
+                assert!(local_solver::solve(
+                    &generated,
+                    &crate::template::Template::Disabled,
+                    &Default::default(),
+                    local_solver::disjoint::DisjointStrategy::Greedy
+                )
+                .is_ok());
+            }
+        }
+    }
+
+    #[test]
+    fn test_huge_counts_are_clamped() {
+        for strategy in [
+            Strategy::Clusters {
+                count: usize::MAX,
+                radius: usize::MAX,
+            },
+            Strategy::Walls {
+                count: usize::MAX,
+                gap: 1,
+            },
+        ] {
+            let config = GeneratorConfig {
+                strategy,
+                pink_pairs: usize::MAX,
+                ..Default::default()
+            };
+            assert!(generate(&field(), &config).is_ok());
+        }
+    }
+}
@@ -0,0 +1,169 @@
+pub mod panel;
+
+use std::io::ErrorKind;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{consts::HISTORY_PATH, error::AppError, scenario::Scenario};
+
+/// A single NEOS submission.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JobRecord {
+    pub timestamp: String,
+    pub template: String,
+    pub solver: String,
+    pub job_number: i32,
+    pub password: String,
+    pub status: String,
+    pub output: Option<String>,
+    pub scenario: Scenario,
+}
+
+/// Every submission made from this working directory, oldest first.
+#[derive(Default, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JobHistory {
+    #[serde(default)]
+    pub records: Vec<JobRecord>,
+    /// Set when an unreadable history file is still in place, so that it
+    /// never gets overwritten.
+    #[serde(skip)]
+    read_only: bool,
+}
+
+impl JobHistory {
+    pub fn load() -> Result<Self, AppError> {
+        Self::load_from(HISTORY_PATH)
+    }
+
+    /// A missing file is an empty history. A file that doesn't parse is
+    /// moved aside to `<path>.bak`, or `<path>.bak.N` if that is taken, and
+    /// reported.
+    pub fn load_from(path: &str) -> Result<Self, AppError> {
+        let content = match std::fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(AppError::FailedLoadHistory(e.to_string())),
+        };
+
+        toml::from_str(&content).map_err(|e| {
+            let backup = backup_path(path);
+            match std::fs::rename(path, &backup) {
+                Ok(_) => AppError::FailedLoadHistory(format!("{}, moved it to {}", e, backup)),
+                Err(_) => AppError::FailedLoadHistory(e.to_string()),
+            }
+        })
+    }
+
+    /// History to use after [`JobHistory::load_from`] failed on `path`.
+    pub fn fallback(path: &str) -> Self {
+        Self {
+            records: Vec::new(),
+            read_only: Path::new(path).exists(),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), AppError> {
+        self.save_to(HISTORY_PATH)
+    }
+
+    pub fn save_to(&self, path: &str) -> Result<(), AppError> {
+        if self.read_only {
+            return Err(AppError::FailedSaveHistory);
+        }
+        let toml_str = toml::to_string_pretty(self).map_err(|_| AppError::FailedSaveHistory)?;
+        std::fs::write(path, toml_str).map_err(|_| AppError::FailedSaveHistory)
+    }
+
+    pub fn add(&mut self, record: JobRecord) {
+        self.records.push(record);
+    }
+
+    pub fn find_mut(&mut self, job_number: i32) -> Option<&mut JobRecord> {
+        self.records
+            .iter_mut()
+            .rev()
+            .find(|record| record.job_number == job_number)
+    }
+}
+
+/// First of `<path>.bak`, `<path>.bak.1`, ... that doesn't exist yet, so
+/// earlier backups are never overwritten.
+fn backup_path(path: &str) -> String {
+    let backup = format!("{}.bak", path);
+    std::iter::once(backup.clone())
+        .chain((1..).map(|n| format!("{}.{}", backup, n)))
+        .find(|candidate| !Path::new(candidate).exists())
+        .unwrap_or(backup)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::field::{
+        cell::{Cell, CellType},
+        Field,
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let mut field = Field::new();
+        field.start_cell = Some(Cell::new(1, 1));
+        field.filled_cells.insert(Cell::new(5, 4), CellType::Green);
+        field.filled_cells.insert(Cell::new(6, 5), CellType::Green);
+        field.handle_add_pink_pair_constraint(Cell::new(5, 5));
+
+        let mut history = JobHistory::default();
+        history.add(JobRecord {
+            timestamp: "2025-05-04 13:07:09".to_string(),
+            template: "path_pink".to_string(),
+            solver: "cbc".to_string(),
+            job_number: 17,
+            password: "secret".to_string(),
+            status: "Done".to_string(),
+            output: Some("--- Path 1 ---\n  (1,1) -> (1,2)\n".to_string()),
+            scenario: Scenario::from_field(&field),
+        });
+        history.find_mut(17).unwrap().status = "Killed".to_string();
+
+        let path = std::env::temp_dir().join(format!("history_test_{}.toml", std::process::id()));
+        let path = path.to_str().unwrap();
+        history.save_to(path).unwrap();
+        let loaded = JobHistory::load_from(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(loaded, history);
+        assert_eq!(loaded.records[0].status, "Killed");
+        assert_eq!(JobHistory::load_from(path).unwrap(), JobHistory::default());
+    }
+
+    #[test]
+    fn test_corrupt_history_is_kept() {
+        let path = std::env::temp_dir().join(format!("history_bad_{}.toml", std::process::id()));
+        let path = path.to_str().unwrap();
+        let backup = format!("{}.bak", path);
+        std::fs::write(path, "records = 3").unwrap();
+
+        assert!(matches!(
+            JobHistory::load_from(path),
+            Err(AppError::FailedLoadHistory(_))
+        ));
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "records = 3");
+        assert!(!JobHistory::fallback(path).read_only);
+
+        // A second corrupt file gets its own backup.
+        std::fs::write(path, "records = 4").unwrap();
+        assert!(JobHistory::load_from(path).is_err());
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), "records = 3");
+        let second = format!("{}.1", backup);
+        assert_eq!(std::fs::read_to_string(&second).unwrap(), "records = 4");
+        std::fs::remove_file(&backup).unwrap();
+        std::fs::remove_file(&second).unwrap();
+
+        std::fs::create_dir(path).unwrap();
+        assert!(JobHistory::load_from(path).is_err());
+        assert!(JobHistory::fallback(path).save_to(path).is_err());
+        std::fs::remove_dir(path).unwrap();
+    }
+}
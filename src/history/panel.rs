@@ -0,0 +1,85 @@
+use eframe::egui::{self, Grid, ScrollArea, Ui};
+
+use super::JobHistory;
+
+pub enum HistoryAction {
+    /// Show the stored scenario and output on the canvas.
+    Open(usize),
+    /// Show the stored scenario and fetch the output from NEOS again.
+    Refetch(usize),
+}
+
+pub struct HistoryPanel {
+    open: bool,
+}
+
+impl HistoryPanel {
+    pub fn new() -> Self {
+        Self { open: false }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+    }
+
+    pub fn show(&mut self, ui: &mut Ui, history: &JobHistory) -> Option<HistoryAction> {
+        let mut action = None;
+
+        egui::Window::new("Job history")
+            .open(&mut self.open)
+            .default_width(500.0)
+            .show(ui.ctx(), |ui| {
+                if history.records.is_empty() {
+                    ui.label("No submissions yet");
+                    return;
+                }
+
+                ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                    Grid::new("job_history")
+                        .striped(true)
+                        .spacing([12.0, 4.0])
+                        .show(ui, |ui| {
+                            for header in ["Time", "Template", "Solver", "Job", "Status", ""] {
+                                ui.strong(header);
+                            }
+                            ui.end_row();
+
+                            for (index, record) in history.records.iter().enumerate().rev() {
+                                ui.label(&record.timestamp);
+                                ui.label(&record.template);
+                                ui.label(&record.solver);
+                                ui.label(record.job_number.to_string());
+                                ui.label(&record.status);
+                                ui.horizontal(|ui| {
+                                    if ui
+                                        .add_enabled(
+                                            record.output.is_some(),
+                                            egui::Button::new("Open"),
+                                        )
+                                        .clicked()
+                                    {
+                                        action = Some(HistoryAction::Open(index));
+                                    }
+                                    if ui.button("Re-fetch").clicked() {
+                                        action = Some(HistoryAction::Refetch(index));
+                                    }
+                                });
+                                ui.end_row();
+                            }
+                        });
+                });
+            });
+
+        action
+    }
+}
+
+impl Default for HistoryPanel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,9 +1,15 @@
 pub mod app;
+pub mod cli;
 pub mod config;
 pub mod consts;
 pub mod error;
+pub mod export;
 pub mod field;
+pub mod generator;
+pub mod history;
+pub mod local_solver;
 pub mod neos;
+pub mod scenario;
 pub mod template;
 pub mod toast;
 pub mod utils;
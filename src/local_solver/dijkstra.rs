@@ -0,0 +1,84 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use crate::field::{cell::Cell, Field};
+
+use super::SearchOptions;
+
+#[derive(PartialEq)]
+struct State {
+    cost: f64,
+    cell: Cell,
+}
+
+impl Eq for State {}
+
+impl Ord for State {
+    // Reversed so that `BinaryHeap` pops the cheapest state first.
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.cell.cmp(&self.cell))
+    }
+}
+
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Cheapest path from `start` to `end` that never enters a cell in `blocked`.
+/// Returns the visited cells (both endpoints included) and the total cost.
+pub(crate) fn shortest_path(
+    field: &Field,
+    options: &SearchOptions,
+    start: Cell,
+    end: Cell,
+    blocked: &HashSet<Cell>,
+) -> Option<(Vec<Cell>, f64)> {
+    let mut dist = HashMap::from([(start, 0.0)]);
+    let mut prev: HashMap<Cell, Cell> = HashMap::new();
+    let mut heap = BinaryHeap::from([State {
+        cost: 0.0,
+        cell: start,
+    }]);
+
+    while let Some(State { cost, cell }) = heap.pop() {
+        if cell == end {
+            let mut cells = vec![end];
+            let mut current = end;
+            while let Some(&p) = prev.get(&current) {
+                cells.push(p);
+                current = p;
+            }
+            cells.reverse();
+            return Some((cells, cost));
+        }
+
+        if cost > dist[&cell] {
+            continue;
+        }
+
+        for (next, move_cost) in options.neighbors(field, &cell) {
+            if blocked.contains(&next) {
+                continue;
+            }
+
+            let next_cost = cost + move_cost;
+            if dist.get(&next).is_none_or(|&d| next_cost < d) {
+                dist.insert(next, next_cost);
+                prev.insert(next, cell);
+                heap.push(State {
+                    cost: next_cost,
+                    cell: next,
+                });
+            }
+        }
+    }
+
+    None
+}
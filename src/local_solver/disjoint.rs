@@ -0,0 +1,228 @@
+use std::collections::{BinaryHeap, HashSet};
+
+use crate::field::{cell::Cell, Field};
+
+use super::{dijkstra, SearchOptions};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DisjointStrategy {
+    /// Shortest path first, then the shortest path avoiding every node used so
+    /// far, and so on. This is what the `path_multiple*` templates do.
+    Greedy,
+    /// The set of node-disjoint paths with the lowest total cost among sets
+    /// with the largest reachable number of paths.
+    MinTotalCost,
+}
+
+impl DisjointStrategy {
+    pub fn variants() -> &'static [DisjointStrategy] {
+        &[DisjointStrategy::Greedy, DisjointStrategy::MinTotalCost]
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            DisjointStrategy::Greedy => "Greedy",
+            DisjointStrategy::MinTotalCost => "Min total cost",
+        }
+    }
+}
+
+pub(crate) fn greedy(
+    field: &Field,
+    options: &SearchOptions,
+    start: Cell,
+    end: Cell,
+    max_paths: usize,
+) -> Vec<Vec<Cell>> {
+    let mut used = HashSet::new();
+    let mut paths = Vec::new();
+
+    while paths.len() < max_paths {
+        let Some((cells, _)) = dijkstra::shortest_path(field, options, start, end, &used) else {
+            break;
+        };
+
+        // A direct start -> terminal move blocks nothing and would repeat forever.
+        let is_direct = cells.len() <= 2;
+        let inner = cells
+            .get(1..cells.len().saturating_sub(1))
+            .unwrap_or_default();
+        used.extend(inner.iter().copied());
+        paths.push(cells);
+
+        if is_direct {
+            break;
+        }
+    }
+
+    paths
+}
+
+struct Edge {
+    to: usize,
+    cap: usize,
+    cost: f64,
+}
+
+/// Residual graph where every cell is split into an `in` node (`2 * i`) and
+/// an `out` node (`2 * i + 1`) joined by a unit capacity edge, so that unit
+/// flows are node-disjoint.
+struct FlowGraph {
+    edges: Vec<Edge>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl FlowGraph {
+    fn add_edge(&mut self, from: usize, to: usize, cap: usize, cost: f64) {
+        self.adjacency[from].push(self.edges.len());
+        self.edges.push(Edge { to, cap, cost });
+        self.adjacency[to].push(self.edges.len());
+        self.edges.push(Edge {
+            to: from,
+            cap: 0,
+            cost: -cost,
+        });
+    }
+}
+
+#[derive(PartialEq)]
+struct State {
+    cost: f64,
+    node: usize,
+}
+
+impl Eq for State {}
+
+impl Ord for State {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Successive shortest paths min-cost flow with Johnson potentials.
+pub(crate) fn min_total_cost(
+    field: &Field,
+    options: &SearchOptions,
+    start: Cell,
+    end: Cell,
+    max_paths: usize,
+) -> Vec<Vec<Cell>> {
+    let index = |cell: &Cell| (cell.y - 1) * field.width + (cell.x - 1);
+    let cell_at = |i: usize| Cell::new(i % field.width + 1, i / field.width + 1);
+
+    let node_count = 2 * field.width * field.height;
+    let mut graph = FlowGraph {
+        edges: Vec::new(),
+        adjacency: vec![Vec::new(); node_count],
+    };
+
+    for i in 0..field.width * field.height {
+        let cell = cell_at(i);
+        if options.avoid_green && field.is_green_cell(&cell) {
+            continue;
+        }
+
+        let cap = if cell == start || cell == end {
+            max_paths
+        } else {
+            1
+        };
+        graph.add_edge(2 * i, 2 * i + 1, cap, 0.0);
+
+        for (next, cost) in options.neighbors(field, &cell) {
+            graph.add_edge(2 * i + 1, 2 * index(&next), 1, cost);
+        }
+    }
+
+    let source = 2 * index(&start) + 1;
+    let sink = 2 * index(&end);
+
+    let mut potential = vec![0.0; node_count];
+    let mut flow = 0;
+
+    while flow < max_paths {
+        let mut dist = vec![f64::INFINITY; node_count];
+        let mut prev_edge = vec![usize::MAX; node_count];
+        let mut heap = BinaryHeap::from([State {
+            cost: 0.0,
+            node: source,
+        }]);
+        dist[source] = 0.0;
+
+        while let Some(State { cost, node }) = heap.pop() {
+            if cost > dist[node] {
+                continue;
+            }
+
+            for &e in &graph.adjacency[node] {
+                let edge = &graph.edges[e];
+                if edge.cap == 0 {
+                    continue;
+                }
+
+                // Rounding may push reduced costs slightly below zero.
+                let reduced = (edge.cost + potential[node] - potential[edge.to]).max(0.0);
+                let next_cost = cost + reduced;
+                if next_cost < dist[edge.to] {
+                    dist[edge.to] = next_cost;
+                    prev_edge[edge.to] = e;
+                    heap.push(State {
+                        cost: next_cost,
+                        node: edge.to,
+                    });
+                }
+            }
+        }
+
+        if dist[sink].is_infinite() {
+            break;
+        }
+
+        for (p, d) in potential.iter_mut().zip(&dist) {
+            if d.is_finite() {
+                *p += d;
+            }
+        }
+
+        let mut node = sink;
+        while node != source {
+            let e = prev_edge[node];
+            graph.edges[e].cap -= 1;
+            graph.edges[e ^ 1].cap += 1;
+            node = graph.edges[e ^ 1].to;
+        }
+        flow += 1;
+    }
+
+    // Walk the used forward edges from the source to split the flow into paths.
+    (0..flow)
+        .map(|_| {
+            let mut cells = vec![start];
+            let mut node = source;
+
+            while node != sink {
+                let e = *graph.adjacency[node]
+                    .iter()
+                    .find(|&&e| e % 2 == 0 && graph.edges[e ^ 1].cap > 0)
+                    .expect("flow must be conserved");
+                graph.edges[e ^ 1].cap -= 1;
+                node = graph.edges[e].to;
+
+                if node % 2 == 0 {
+                    cells.push(cell_at(node / 2));
+                }
+            }
+
+            cells
+        })
+        .collect()
+}
@@ -0,0 +1,385 @@
+mod dijkstra;
+pub mod disjoint;
+
+use std::collections::HashSet;
+
+use crate::{
+    error::AppError,
+    field::{cell::Cell, path::Path, Field},
+    local_solver::disjoint::DisjointStrategy,
+    template::{param::ParamValues, Template},
+};
+
+pub const STRAIGHT_MOVE_COST: f64 = 1.0;
+pub const DIAGONAL_MOVE_COST: f64 = 1.4;
+/// Default of the `Ns` parameter of the multiple path templates.
+pub const DEFAULT_MAX_PATHS: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Neighborhood {
+    Four,
+    Eight,
+}
+
+/// Grid model used by the local solver, mirroring what an AMPL template
+/// allows: which moves exist, what they cost and whether green cells block.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SearchOptions {
+    pub neighborhood: Neighborhood,
+    pub diagonal_cost: f64,
+    pub avoid_green: bool,
+}
+
+impl SearchOptions {
+    /// Options reproducing the objective of `template`, if the local solver
+    /// supports it.
+    pub fn for_template(template: &Template) -> Option<Self> {
+        match template {
+            // `path` and `path_disabled` only move horizontally or vertically.
+            Template::Default => Some(Self {
+                neighborhood: Neighborhood::Four,
+                diagonal_cost: DIAGONAL_MOVE_COST,
+                avoid_green: false,
+            }),
+            Template::Disabled => Some(Self {
+                neighborhood: Neighborhood::Four,
+                diagonal_cost: DIAGONAL_MOVE_COST,
+                avoid_green: true,
+            }),
+            // `path_8` and `path_multiple` count every move, diagonal or not,
+            // as a single step.
+            Template::Eight | Template::Multiple => Some(Self {
+                neighborhood: Neighborhood::Eight,
+                diagonal_cost: STRAIGHT_MOVE_COST,
+                avoid_green: true,
+            }),
+            // These two weight diagonals with 1.4142.
+            Template::MultipleSections | Template::MultipleSeparated => Some(Self {
+                neighborhood: Neighborhood::Eight,
+                diagonal_cost: std::f64::consts::SQRT_2,
+                avoid_green: true,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Same moves as `path_8`, weighted with the 1 / 1.4 costs of `path_full`.
+    pub fn weighted() -> Self {
+        Self {
+            neighborhood: Neighborhood::Eight,
+            diagonal_cost: DIAGONAL_MOVE_COST,
+            avoid_green: true,
+        }
+    }
+
+    pub fn move_cost(&self, from: &Cell, to: &Cell) -> f64 {
+        if from.x != to.x && from.y != to.y {
+            self.diagonal_cost
+        } else {
+            STRAIGHT_MOVE_COST
+        }
+    }
+
+    pub fn path_cost(&self, path: &Path) -> f64 {
+        path.cells()
+            .windows(2)
+            .map(|w| self.move_cost(&w[0], &w[1]))
+            .sum()
+    }
+
+    fn neighbors(&self, field: &Field, cell: &Cell) -> Vec<(Cell, f64)> {
+        let offsets: &[(i64, i64)] = match self.neighborhood {
+            Neighborhood::Four => &[(1, 0), (-1, 0), (0, 1), (0, -1)],
+            Neighborhood::Eight => &[
+                (1, 0),
+                (-1, 0),
+                (0, 1),
+                (0, -1),
+                (1, 1),
+                (1, -1),
+                (-1, 1),
+                (-1, -1),
+            ],
+        };
+
+        offsets
+            .iter()
+            .filter_map(|(dx, dy)| {
+                let x = cell.x as i64 + dx;
+                let y = cell.y as i64 + dy;
+                if x < 1 || y < 1 {
+                    return None;
+                }
+
+                let next = Cell::new(x as usize, y as usize);
+                if !field.contains(&next) || (self.avoid_green && field.is_green_cell(&next)) {
+                    return None;
+                }
+
+                Some((next, self.move_cost(cell, &next)))
+            })
+            .collect()
+    }
+}
+
+/// Start and terminal of `field`, which must be distinct cells.
+fn endpoints(field: &Field) -> Result<(Cell, Cell), AppError> {
+    let start = field.start_cell.ok_or(AppError::StartNotSet)?;
+    let end = field.end_cell.ok_or(AppError::EndNotSet)?;
+
+    if start == end {
+        return Err(AppError::StartIsEnd);
+    }
+    Ok((start, end))
+}
+
+/// Cheapest path from start to terminal under `options`.
+pub fn shortest_path(field: &Field, options: &SearchOptions) -> Result<Path, AppError> {
+    let (start, end) = endpoints(field)?;
+
+    dijkstra::shortest_path(field, options, start, end, &HashSet::new())
+        .map(|(cells, _)| Path::new(cells, 0))
+        .ok_or(AppError::NoPathFound)
+}
+
+/// Up to `max_paths` paths from start to terminal sharing no cells except
+/// the endpoints.
+pub fn disjoint_paths(
+    field: &Field,
+    options: &SearchOptions,
+    max_paths: usize,
+    strategy: DisjointStrategy,
+) -> Result<Vec<Path>, AppError> {
+    let (start, end) = endpoints(field)?;
+
+    let paths = match strategy {
+        DisjointStrategy::Greedy => disjoint::greedy(field, options, start, end, max_paths),
+        DisjointStrategy::MinTotalCost => {
+            disjoint::min_total_cost(field, options, start, end, max_paths)
+        }
+    };
+
+    if paths.is_empty() {
+        return Err(AppError::NoPathFound);
+    }
+
+    Ok(paths
+        .into_iter()
+        .enumerate()
+        .map(|(id, cells)| Path::new(cells, id))
+        .collect())
+}
+
+/// Solves `template` on `field` without contacting NEOS. Templates with an
+/// `Ns` parameter get up to `Ns` disjoint paths found with `strategy`.
+///
+/// Turn costs, pink pairs, corner cutting and node limits are not modeled,
+/// so their templates are rejected rather than answered with paths NEOS
+/// would not return.
+pub fn solve(
+    field: &Field,
+    template: &Template,
+    params: &ParamValues,
+    strategy: DisjointStrategy,
+) -> Result<Vec<Path>, AppError> {
+    let options = SearchOptions::for_template(template)
+        .ok_or_else(|| AppError::UnsupportedTemplate(template.name().to_string()))?;
+
+    match template.max_paths(params) {
+        Some(max_paths) => disjoint_paths(field, &options, max_paths, strategy),
+        None => Ok(vec![shortest_path(field, &options)?]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::field::cell::CellType;
+    use crate::template::{param::ParamValue, MAX_PATHS_PARAM};
+
+    use super::*;
+
+    fn field(width: usize, height: usize, start: Cell, end: Cell, green: &[Cell]) -> Field {
+        let mut field = Field::new();
+        field.width = width;
+        field.height = height;
+        field.start_cell = Some(start);
+        field.end_cell = Some(end);
+        for cell in green {
+            field.filled_cells.insert(*cell, CellType::Green);
+        }
+        field
+    }
+
+    #[test]
+    fn test_four_connected() {
+        let field = field(5, 5, Cell::new(1, 1), Cell::new(4, 3), &[]);
+        let path = solve(
+            &field,
+            &Template::Default,
+            &ParamValues::new(),
+            DisjointStrategy::Greedy,
+        )
+        .unwrap()
+        .remove(0);
+
+        assert_eq!(path.cells().len(), 6);
+        assert_eq!(path.cells().first(), Some(&Cell::new(1, 1)));
+        assert_eq!(path.cells().last(), Some(&Cell::new(4, 3)));
+    }
+
+    #[test]
+    fn test_green_cells_are_avoided() {
+        let wall = [Cell::new(2, 1), Cell::new(2, 2)];
+        let field = field(3, 3, Cell::new(1, 1), Cell::new(3, 1), &wall);
+
+        let path = solve(
+            &field,
+            &Template::Disabled,
+            &ParamValues::new(),
+            DisjointStrategy::Greedy,
+        )
+        .unwrap()
+        .remove(0);
+        assert!(path.cells().iter().all(|c| !wall.contains(c)));
+        assert_eq!(path.cells().len(), 7);
+
+        // `path` ignores obstacles entirely.
+        let path = solve(
+            &field,
+            &Template::Default,
+            &ParamValues::new(),
+            DisjointStrategy::Greedy,
+        )
+        .unwrap()
+        .remove(0);
+        assert_eq!(path.cells().len(), 3);
+    }
+
+    #[test]
+    fn test_eight_connected_costs() {
+        let field = field(5, 5, Cell::new(1, 1), Cell::new(4, 4), &[]);
+
+        let path = solve(
+            &field,
+            &Template::Eight,
+            &ParamValues::new(),
+            DisjointStrategy::Greedy,
+        )
+        .unwrap()
+        .remove(0);
+        assert_eq!(path.cells().len(), 4);
+
+        let options = SearchOptions::weighted();
+        let path = shortest_path(&field, &options).unwrap();
+        assert!((options.path_cost(&path) - 3.0 * DIAGONAL_MOVE_COST).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_no_path() {
+        let wall = [Cell::new(2, 1), Cell::new(2, 2), Cell::new(2, 3)];
+        let field = field(3, 3, Cell::new(1, 1), Cell::new(3, 1), &wall);
+
+        assert!(matches!(
+            solve(
+                &field,
+                &Template::Disabled,
+                &ParamValues::new(),
+                DisjointStrategy::Greedy
+            ),
+            Err(AppError::NoPathFound)
+        ));
+    }
+
+    #[test]
+    fn test_start_is_end() {
+        let field = field(3, 3, Cell::new(2, 2), Cell::new(2, 2), &[]);
+
+        for strategy in DisjointStrategy::variants() {
+            assert!(matches!(
+                solve(&field, &Template::Multiple, &ParamValues::new(), *strategy),
+                Err(AppError::StartIsEnd)
+            ));
+        }
+
+        let options = SearchOptions::weighted();
+        let paths = disjoint::greedy(&field, &options, Cell::new(2, 2), Cell::new(2, 2), 5);
+        assert_eq!(paths, vec![vec![Cell::new(2, 2)]]);
+    }
+
+    #[test]
+    fn test_max_paths() {
+        let field = field(4, 4, Cell::new(1, 1), Cell::new(4, 4), &[]);
+        let params = ParamValues::from([(MAX_PATHS_PARAM.to_string(), ParamValue::Int(2))]);
+
+        for template in [
+            Template::Multiple,
+            Template::MultipleSections,
+            Template::MultipleSeparated,
+        ] {
+            let paths = solve(&field, &template, &params, DisjointStrategy::Greedy).unwrap();
+            assert_eq!(paths.len(), 2);
+            assert_disjoint(&paths);
+        }
+
+        for template in [
+            Template::TurnCost,
+            Template::Pink,
+            Template::CornerCutting,
+            Template::Full,
+        ] {
+            assert!(matches!(
+                solve(&field, &template, &params, DisjointStrategy::Greedy),
+                Err(AppError::UnsupportedTemplate(_))
+            ));
+        }
+    }
+
+    fn assert_disjoint(paths: &[Path]) {
+        let mut seen = HashSet::new();
+        for path in paths {
+            for cell in &path.cells()[1..path.cells().len() - 1] {
+                assert!(seen.insert(*cell), "{:?} used twice", cell);
+            }
+        }
+    }
+
+    #[test]
+    fn test_disjoint_strategies() {
+        //   S . .
+        //   . . .
+        //   . . T
+        let field = field(3, 3, Cell::new(1, 1), Cell::new(3, 3), &[]);
+        let options = SearchOptions::weighted();
+
+        let greedy = disjoint_paths(&field, &options, 3, DisjointStrategy::Greedy).unwrap();
+        let optimal = disjoint_paths(&field, &options, 3, DisjointStrategy::MinTotalCost).unwrap();
+        assert_disjoint(&greedy);
+        assert_disjoint(&optimal);
+        assert!(optimal.len() >= greedy.len());
+        assert_eq!(optimal.len(), 3);
+
+        for path in &optimal {
+            assert_eq!(path.cells().first(), Some(&Cell::new(1, 1)));
+            assert_eq!(path.cells().last(), Some(&Cell::new(3, 3)));
+        }
+    }
+
+    #[test]
+    fn test_min_total_cost_with_walls() {
+        let wall = [
+            Cell::new(3, 1),
+            Cell::new(3, 2),
+            Cell::new(3, 4),
+            Cell::new(3, 5),
+        ];
+        let field = field(5, 5, Cell::new(1, 3), Cell::new(5, 3), &wall);
+        let options = SearchOptions::for_template(&Template::Multiple).unwrap();
+
+        // Only one gap in the wall, so a single path exists.
+        for strategy in DisjointStrategy::variants() {
+            let paths = disjoint_paths(&field, &options, 5, *strategy).unwrap();
+            assert_eq!(paths.len(), 1);
+            assert!(paths[0].cells().contains(&Cell::new(3, 3)));
+        }
+    }
+}
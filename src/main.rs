@@ -1,7 +1,19 @@
 use eframe::egui::{self};
 use interference_generator::app::App;
+use interference_generator::cli;
 
 fn main() -> eframe::Result {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // Any arguments switch to the headless mode, the GUI takes none.
+    if !args.is_empty() {
+        if let Err(e) = cli::run(args) {
+            eprintln!("error: {}\n\n{}", e, cli::USAGE);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default().with_inner_size([1280.0, 520.0]),
         ..Default::default()
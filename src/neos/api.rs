@@ -1,36 +1,52 @@
+use std::collections::HashMap;
 use std::sync::{
     mpsc::{Receiver, Sender},
     Arc,
 };
 
 use dxr_client::{Client, ClientBuilder, Url};
+use tokio::task::AbortHandle;
 
-use crate::consts::NEOS_API_URL;
+use crate::consts::{NEOS_API_URL, NEOS_CATEGORY, NEOS_MAX_POLL_BACKOFF, NEOS_POLL_INTERVAL};
 
+use super::error::NeosError;
+use super::jobs::JobId;
 use super::response::NeosResponse;
+use super::solver::{solvers_from_neos_list, Solver};
+use super::transport::{call, RetryPolicy};
 
 pub struct NeosAPI {
     client: Arc<Client>,
     tx: Sender<NeosResponse>,
     pub rx: Receiver<NeosResponse>,
     pub response: String,
-    pub is_solving_task: bool,
+    /// Solvers NEOS reported for the category, once it answered.
+    pub solvers: Option<Vec<Solver>>,
+    /// Polling tasks of the jobs currently being waited on.
+    poll_tasks: HashMap<JobId, AbortHandle>,
 }
 
 impl NeosAPI {
-    pub fn new() -> Self {
+    pub fn new(url: Url) -> Self {
         let (tx, rx) = std::sync::mpsc::channel();
-        let url = Url::parse(NEOS_API_URL).unwrap();
 
         Self {
             client: Arc::new(ClientBuilder::new(url).build()),
             tx,
             rx,
             response: String::new(),
-            is_solving_task: false,
+            solvers: None,
+            poll_tasks: HashMap::new(),
         }
     }
 
+    /// Sends future calls to another endpoint. Jobs already being polled
+    /// keep using the old one.
+    pub fn set_url(&mut self, url: Url) {
+        self.client = Arc::new(ClientBuilder::new(url).build());
+        self.solvers = None;
+    }
+
     fn clone_client_tx(&self) -> (Arc<Client>, Sender<NeosResponse>) {
         (Arc::clone(&self.client), self.tx.clone())
     }
@@ -39,52 +55,260 @@ impl NeosAPI {
         let (client, tx) = self.clone_client_tx();
 
         tokio::spawn(async move {
-            let response: Result<String, dxr_client::ClientError> = client.call("ping", ()).await;
-            if let Ok(body) = response {
-                let _ = tx.send(NeosResponse::Message(body));
-            }
+            let response = match call(&client, "ping", (), RetryPolicy::DEFAULT).await {
+                Ok(body) => NeosResponse::Message(body),
+                Err(e) => NeosResponse::Error(e),
+            };
+            let _ = tx.send(response);
         });
     }
 
-    pub fn submit_job(&mut self, input: String) {
+    /// Solvers to offer, falling back to the built-in list while NEOS
+    /// hasn't answered or is unreachable.
+    pub fn available_solvers(&self) -> &[Solver] {
+        match &self.solvers {
+            Some(solvers) if !solvers.is_empty() => solvers,
+            _ => Solver::variants(),
+        }
+    }
+
+    pub fn list_solvers(&self) {
         let (client, tx) = self.clone_client_tx();
-        self.is_solving_task = true;
 
         tokio::spawn(async move {
-            let response: Result<(i32, String), dxr_client::ClientError> =
-                client.call("submitJob", input).await;
-
-            if let Ok(body) = response {
-                match body {
-                    (0, error_msg) => {
-                        let _ = tx.send(NeosResponse::Error(error_msg));
-                    }
-                    (job_number, job_password) => {
-                        let _ = tx.send(NeosResponse::JobCredentials(job_number, job_password));
-                    }
+            let response = match list_solvers(&client).await {
+                Ok(solvers) => NeosResponse::Solvers(solvers),
+                Err(e) => NeosResponse::Error(e),
+            };
+            let _ = tx.send(response);
+        });
+    }
+
+    pub fn submit_job(&self, id: JobId, input: String) {
+        let (client, tx) = self.clone_client_tx();
+
+        tokio::spawn(async move {
+            let response = match call(&client, "submitJob", input, RetryPolicy::ONCE).await {
+                Ok((0, error_msg)) => NeosResponse::JobFailed(id, NeosError::Rejected(error_msg)),
+                Ok((job_number, job_password)) => {
+                    NeosResponse::JobCredentials(id, job_number, job_password)
                 }
-            }
+                Err(e) => NeosResponse::JobFailed(id, e),
+            };
+            let _ = tx.send(response);
         });
     }
 
-    pub fn get_final_results(&self, job_number: i32, job_password: String) {
+    pub fn get_final_results(&self, id: JobId, job_number: i32, job_password: String) {
         let (client, tx) = self.clone_client_tx();
 
         tokio::spawn(async move {
-            let response: Result<Vec<u8>, dxr_client::ClientError> = client
-                .call("getFinalResults", (job_number, job_password))
-                .await;
+            let result =
+                fetch_final_results(&client, job_number, &job_password, RetryPolicy::DEFAULT).await;
+            let _ = tx.send(job_result(id, result));
+        });
+    }
 
-            if let Ok(body) = response {
-                let output = String::from_utf8(body).unwrap();
-                let _ = tx.send(NeosResponse::JobOuput(output));
-            }
+    /// Polls the job status until NEOS reports it as done, streaming new
+    /// solver log output on the way, then fetches the final results.
+    pub fn poll_job(&mut self, id: JobId, job_number: i32, job_password: String) {
+        self.finish_job(id);
+        let (client, tx) = self.clone_client_tx();
+
+        let task = tokio::spawn(async move {
+            let result = wait_for_job(&client, &tx, id, job_number, job_password).await;
+            let _ = tx.send(job_result(id, result));
+        });
+        self.poll_tasks.insert(id, task.abort_handle());
+    }
+
+    /// Stops waiting on the job and asks NEOS to kill it.
+    pub fn kill_job(&mut self, id: JobId, job_number: i32, job_password: String) {
+        self.finish_job(id);
+
+        let (client, tx) = self.clone_client_tx();
+
+        tokio::spawn(async move {
+            let params = (job_number, job_password, "Cancelled by user");
+            let response = match call(&client, "killJob", params, RetryPolicy::DEFAULT).await {
+                Ok(body) => NeosResponse::JobKilled(id, body),
+                Err(e) => NeosResponse::KillFailed(id, e),
+            };
+            let _ = tx.send(response);
         });
     }
+
+    /// Stops and forgets the polling task of a job, once it reached a final
+    /// state or before it is polled again.
+    pub fn finish_job(&mut self, id: JobId) {
+        if let Some(task) = self.poll_tasks.remove(&id) {
+            task.abort();
+        }
+    }
+}
+
+/// NEOS reports `Waiting` and `Running` while the job is queued or solving,
+/// anything else (`Done`, `Unknown Job`, `Bad Password`) ends the job.
+fn is_job_running(status: &str) -> bool {
+    matches!(status, "" | "Waiting" | "Running")
+}
+
+fn job_result(id: JobId, result: Result<String, NeosError>) -> NeosResponse {
+    match result {
+        Ok(output) => NeosResponse::JobOuput(id, output),
+        Err(e) => NeosResponse::JobFailed(id, e),
+    }
+}
+
+async fn list_solvers(client: &Client) -> Result<Vec<Solver>, NeosError> {
+    let categories: HashMap<String, String> =
+        call(client, "listCategories", (), RetryPolicy::DEFAULT).await?;
+
+    if !categories.contains_key(NEOS_CATEGORY) {
+        return Err(NeosError::InvalidResponse(format!(
+            "no {} category",
+            NEOS_CATEGORY
+        )));
+    }
+
+    let entries: Vec<String> = call(
+        client,
+        "listSolversInCategory",
+        NEOS_CATEGORY,
+        RetryPolicy::DEFAULT,
+    )
+    .await?;
+
+    Ok(solvers_from_neos_list(&entries))
+}
+
+/// Polls the job until it ends. A lost connection only delays the job, it
+/// fails once NEOS rejects it, faults or returns unreadable output.
+async fn wait_for_job(
+    client: &Client,
+    tx: &Sender<NeosResponse>,
+    id: JobId,
+    job_number: i32,
+    job_password: String,
+) -> Result<String, NeosError> {
+    let mut last_status = String::new();
+    let mut offset = 0;
+    let mut backoff = NEOS_POLL_INTERVAL;
+
+    loop {
+        let result = check_job(
+            client,
+            tx,
+            id,
+            (job_number, &job_password),
+            &mut last_status,
+            &mut offset,
+        )
+        .await;
+
+        let delay = match result {
+            Ok(Some(output)) => return Ok(output),
+            Ok(None) => {
+                backoff = NEOS_POLL_INTERVAL;
+                NEOS_POLL_INTERVAL
+            }
+            Err(e) if is_fatal(&e) => return Err(e),
+            Err(_) => {
+                // Report the real status again once NEOS answers.
+                last_status.clear();
+                let status = "Connection lost, retrying…".to_string();
+                let _ = tx.send(NeosResponse::JobStatus(id, status));
+
+                let delay = backoff;
+                backoff = (backoff * 2).min(NEOS_MAX_POLL_BACKOFF);
+                delay
+            }
+        };
+
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Errors that end a job, anything else is retried by [`wait_for_job`].
+fn is_fatal(error: &NeosError) -> bool {
+    matches!(
+        error,
+        NeosError::Rejected(_) | NeosError::Fault(_) | NeosError::NonUtf8Output
+    )
+}
+
+/// One round of polling: forwards status changes and new intermediate
+/// output, and returns the final output once the job ended.
+async fn check_job(
+    client: &Client,
+    tx: &Sender<NeosResponse>,
+    id: JobId,
+    (job_number, job_password): (i32, &str),
+    last_status: &mut String,
+    offset: &mut i32,
+) -> Result<Option<String>, NeosError> {
+    let status: String = call(
+        client,
+        "getJobStatus",
+        (job_number, job_password),
+        RetryPolicy::ONCE,
+    )
+    .await?;
+
+    if matches!(status.as_str(), "Unknown Job" | "Bad Password") {
+        return Err(NeosError::Rejected(format!(
+            "Job {}: {}",
+            job_number, status
+        )));
+    }
+
+    if status != *last_status {
+        *last_status = status.clone();
+        let _ = tx.send(NeosResponse::JobStatus(id, status));
+    }
+
+    let (body, new_offset): (Vec<u8>, i32) = call(
+        client,
+        "getIntermediateResults",
+        (job_number, job_password, *offset),
+        RetryPolicy::ONCE,
+    )
+    .await?;
+
+    *offset = new_offset;
+    if !body.is_empty() {
+        let output = String::from_utf8_lossy(&body).into_owned();
+        let _ = tx.send(NeosResponse::IntermediateOutput(id, output));
+    }
+
+    if is_job_running(last_status) {
+        return Ok(None);
+    }
+
+    fetch_final_results(client, job_number, job_password, RetryPolicy::ONCE)
+        .await
+        .map(Some)
+}
+
+async fn fetch_final_results(
+    client: &Client,
+    job_number: i32,
+    job_password: &str,
+    policy: RetryPolicy,
+) -> Result<String, NeosError> {
+    let body: Vec<u8> = call(
+        client,
+        "getFinalResults",
+        (job_number, job_password),
+        policy,
+    )
+    .await?;
+
+    String::from_utf8(body).map_err(|_| NeosError::NonUtf8Output)
 }
 
 impl Default for NeosAPI {
     fn default() -> Self {
-        Self::new()
+        Self::new(Url::parse(NEOS_API_URL).unwrap())
     }
 }
@@ -0,0 +1,159 @@
+use eframe::egui::{Button, DragValue, Grid, Id, Modal, Ui};
+
+use super::solver::Solver;
+
+pub struct AttachDialog {
+    open: bool,
+    job_number: i32,
+    password_buffer: String,
+}
+
+impl AttachDialog {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            job_number: 0,
+            password_buffer: String::new(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn open(&mut self) {
+        self.open = true;
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// Returns the job number and password once the user confirms.
+    pub fn show(&mut self, ui: &mut Ui) -> Option<(i32, String)> {
+        let mut credentials = None;
+
+        Modal::new(Id::new("ATTACH MODAL")).show(ui.ctx(), |ui| {
+            ui.set_width(250.0);
+
+            ui.heading("Attach to job");
+
+            Grid::new("attach_grid").num_columns(2).show(ui, |ui| {
+                ui.label("Job number:");
+                ui.add(DragValue::new(&mut self.job_number).range(1..=i32::MAX));
+                ui.end_row();
+
+                ui.label("Password:");
+                ui.text_edit_singleline(&mut self.password_buffer);
+                ui.end_row();
+            });
+
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                let valid = self.job_number > 0 && !self.password_buffer.trim().is_empty();
+
+                if ui.add_enabled(valid, Button::new("Attach")).clicked() {
+                    credentials = Some((self.job_number, self.password_buffer.trim().to_string()));
+                    self.close();
+                }
+
+                ui.add_space(10.0);
+
+                if ui.button("Cancel").clicked() {
+                    self.close();
+                }
+            });
+        });
+
+        credentials
+    }
+}
+
+impl Default for AttachDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct CompareDialog {
+    open: bool,
+    selected: Vec<Solver>,
+}
+
+impl CompareDialog {
+    pub fn new() -> Self {
+        Self {
+            open: false,
+            selected: Solver::variants().to_vec(),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn open(&mut self) {
+        self.open = true;
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+    }
+
+    /// Returns the solvers to compare once the user confirms.
+    pub fn show(&mut self, ui: &mut Ui, available: &[Solver]) -> Option<Vec<Solver>> {
+        let mut solvers = None;
+
+        Modal::new(Id::new("COMPARE MODAL")).show(ui.ctx(), |ui| {
+            ui.set_width(250.0);
+
+            ui.heading("Compare solvers");
+
+            for solver in available {
+                let mut checked = self.selected.contains(solver);
+                if ui.checkbox(&mut checked, solver.name()).changed() {
+                    if checked {
+                        self.selected.push(solver.clone());
+                    } else {
+                        self.selected.retain(|s| s != solver);
+                    }
+                }
+            }
+
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                let any_selected = available.iter().any(|s| self.selected.contains(s));
+                if ui
+                    .add_enabled(any_selected, Button::new("Submit"))
+                    .clicked()
+                {
+                    // Keep the order of the solver list regardless of clicks.
+                    solvers = Some(
+                        available
+                            .iter()
+                            .filter(|solver| self.selected.contains(solver))
+                            .cloned()
+                            .collect(),
+                    );
+                    self.close();
+                }
+
+                ui.add_space(10.0);
+
+                if ui.button("Cancel").clicked() {
+                    self.close();
+                }
+            });
+        });
+
+        solvers
+    }
+}
+
+impl Default for CompareDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,52 @@
+use std::fmt;
+
+use dxr_client::ClientError;
+
+/// Why a call to NEOS or one of its jobs failed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NeosError {
+    /// The server could not be reached at all.
+    Unreachable(String),
+    /// No answer within [`crate::consts::NEOS_CALL_TIMEOUT`].
+    Timeout,
+    /// NEOS refused the job or doesn't know it.
+    Rejected(String),
+    /// XML-RPC fault returned by the server.
+    Fault(String),
+    /// The answer wasn't the XML-RPC value that was expected.
+    InvalidResponse(String),
+    NonUtf8Output,
+}
+
+impl NeosError {
+    /// Whether the same call may succeed when repeated.
+    pub fn is_transient(&self) -> bool {
+        matches!(self, NeosError::Unreachable(_) | NeosError::Timeout)
+    }
+}
+
+impl From<ClientError> for NeosError {
+    fn from(error: ClientError) -> Self {
+        match error {
+            ClientError::Fault { fault } => NeosError::Fault(fault.string().to_string()),
+            ClientError::RPC { error } => NeosError::InvalidResponse(error.to_string()),
+            ClientError::Net { error } if error.is_timeout() => NeosError::Timeout,
+            ClientError::Net { error } => NeosError::Unreachable(error.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for NeosError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NeosError::Unreachable(e) => write!(f, "NEOS is unreachable: {}", e),
+            NeosError::Timeout => write!(f, "NEOS did not answer in time"),
+            NeosError::Rejected(msg) => write!(f, "NEOS rejected the job: {}", msg),
+            NeosError::Fault(msg) => write!(f, "NEOS error: {}", msg),
+            NeosError::InvalidResponse(e) => write!(f, "Invalid response from NEOS: {}", e),
+            NeosError::NonUtf8Output => write!(f, "NEOS returned non-UTF-8 output"),
+        }
+    }
+}
+
+impl std::error::Error for NeosError {}
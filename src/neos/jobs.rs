@@ -0,0 +1,181 @@
+use crate::scenario::Scenario;
+use crate::template::Template;
+
+use super::report::{parse_solve_report, SolveReport};
+use super::solver::Solver;
+
+/// Local identifier of a submission, known before NEOS assigns a job number.
+pub type JobId = usize;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JobState {
+    Submitting,
+    Running,
+    Done,
+    Failed,
+    Killed,
+}
+
+impl JobState {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, JobState::Done | JobState::Failed | JobState::Killed)
+    }
+}
+
+pub struct NeosJob {
+    pub id: JobId,
+    pub template: Template,
+    pub solver: Solver,
+    /// Field the job was submitted for.
+    pub scenario: Scenario,
+    pub credentials: Option<(i32, String)>,
+    pub state: JobState,
+    /// Last status message reported by NEOS or the error that ended the job.
+    pub status: String,
+    pub output: String,
+    pub report: Option<SolveReport>,
+}
+
+impl NeosJob {
+    pub fn number(&self) -> Option<i32> {
+        self.credentials.as_ref().map(|(number, _)| *number)
+    }
+}
+
+/// Keeps track of every NEOS job started in this session.
+#[derive(Default)]
+pub struct JobManager {
+    jobs: Vec<NeosJob>,
+    next_id: JobId,
+    /// Job whose result is shown on the canvas.
+    pub selected: Option<JobId>,
+}
+
+impl JobManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a job and returns its id. Pass `credentials` for jobs
+    /// that already exist on NEOS, e.g. when attaching to them.
+    pub fn add(
+        &mut self,
+        template: Template,
+        solver: Solver,
+        scenario: Scenario,
+        credentials: Option<(i32, String)>,
+    ) -> JobId {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let state = match credentials {
+            Some(_) => JobState::Running,
+            None => JobState::Submitting,
+        };
+
+        self.jobs.push(NeosJob {
+            id,
+            template,
+            solver,
+            scenario,
+            credentials,
+            state,
+            status: String::new(),
+            output: String::new(),
+            report: None,
+        });
+
+        id
+    }
+
+    pub fn jobs(&self) -> &[NeosJob] {
+        &self.jobs
+    }
+
+    pub fn get(&self, id: JobId) -> Option<&NeosJob> {
+        self.jobs.iter().find(|job| job.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: JobId) -> Option<&mut NeosJob> {
+        self.jobs.iter_mut().find(|job| job.id == id)
+    }
+
+    pub fn selected(&self) -> Option<&NeosJob> {
+        self.selected.and_then(|id| self.get(id))
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.jobs.iter().any(|job| !job.state.is_finished())
+    }
+
+    /// Stores the final output and parses its solve report.
+    pub fn finish(&mut self, id: JobId, output: String) -> Option<&NeosJob> {
+        let job = self.get_mut(id)?;
+        job.state = JobState::Done;
+        job.report = Some(parse_solve_report(&output));
+        job.output = output;
+        Some(job)
+    }
+
+    pub fn fail(&mut self, id: JobId, state: JobState, message: String) {
+        if let Some(job) = self.get_mut(id) {
+            job.state = state;
+            job.status = message;
+        }
+    }
+
+    /// Drops a finished job from the list.
+    pub fn remove(&mut self, id: JobId) {
+        self.jobs
+            .retain(|job| job.id != id || !job.state.is_finished());
+        if self
+            .selected
+            .is_some_and(|selected| self.get(selected).is_none())
+        {
+            self.selected = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::Field;
+
+    fn add_job(manager: &mut JobManager) -> JobId {
+        let scenario = Scenario::from_field(&Field::new());
+        manager.add(Template::Default, Solver::Cbc, scenario, None)
+    }
+
+    #[test]
+    fn test_jobs_are_tracked_independently() {
+        let mut manager = JobManager::new();
+        let first = add_job(&mut manager);
+        let second = add_job(&mut manager);
+        assert_ne!(first, second);
+        assert!(manager.is_running());
+
+        manager.finish(first, "Objective = 3".to_string());
+        manager.fail(second, JobState::Failed, "Bad Password".to_string());
+
+        assert_eq!(manager.get(first).unwrap().state, JobState::Done);
+        assert_eq!(manager.get(second).unwrap().status, "Bad Password");
+        assert!(!manager.is_running());
+    }
+
+    #[test]
+    fn test_remove_keeps_running_jobs() {
+        let mut manager = JobManager::new();
+        let running = add_job(&mut manager);
+        let done = add_job(&mut manager);
+        manager.finish(done, String::new());
+        manager.selected = Some(done);
+
+        manager.remove(running);
+        manager.remove(done);
+
+        assert!(manager.get(running).is_some());
+        assert!(manager.get(done).is_none());
+        assert_eq!(manager.selected, None);
+    }
+}
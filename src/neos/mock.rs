@@ -0,0 +1,642 @@
+//! Stand-in for the NEOS XML-RPC endpoint, used by the tests and for
+//! offline demos (`interference_generator mock-neos`).
+
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use dxr::{Fault, FaultResponse, MethodCall, MethodResponse, TryFromParams, TryToValue, Value};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+use crate::consts::{NEOS_CATEGORY, NEOS_INPUT_TYPE};
+use crate::error::AppError;
+use crate::field::cell::{Cell, CellType};
+use crate::field::Field;
+use crate::local_solver::{self, disjoint::DisjointStrategy};
+use crate::scenario::{FilledCell, Scenario, SCENARIO_VERSION};
+use crate::template::param::{ParamValue, ParamValues};
+use crate::template::registry::TemplateRegistry;
+use crate::template::{Template, MAX_PATHS_PARAM};
+
+use super::solver::Solver;
+
+/// How the server answers submitted jobs.
+#[derive(Clone)]
+pub enum MockAnswer {
+    /// Rebuild the field from the AMPL data and solve it locally.
+    LocalSolver,
+    /// Reply to every job with the same output.
+    Fixed(String),
+}
+
+struct MockJob {
+    password: String,
+    output: String,
+    killed: bool,
+}
+
+struct MockState {
+    answer: MockAnswer,
+    jobs: HashMap<i32, MockJob>,
+    next_number: i32,
+    /// Calls per method to drop without answering, simulating outages.
+    dropped_calls: HashMap<String, usize>,
+}
+
+pub struct MockNeosServer {
+    address: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    task: JoinHandle<()>,
+}
+
+impl MockNeosServer {
+    /// Binds to `address` (use port 0 for any free port) and starts serving.
+    pub async fn start(address: &str, answer: MockAnswer) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(address).await?;
+        let address = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(MockState {
+            answer,
+            jobs: HashMap::new(),
+            next_number: 1,
+            dropped_calls: HashMap::new(),
+        }));
+
+        let shared = Arc::clone(&state);
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = Arc::clone(&shared);
+                tokio::spawn(async move {
+                    let _ = serve_connection(stream, &state).await;
+                });
+            }
+        });
+
+        Ok(Self {
+            address,
+            state,
+            task,
+        })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.address)
+    }
+
+    /// Serves until the listener fails.
+    pub async fn wait(self) {
+        let _ = self.task.await;
+    }
+
+    /// Closes the connection instead of answering the next `count` calls of
+    /// `method`.
+    pub fn drop_calls(&self, method: &str, count: usize) {
+        let mut state = self.state.lock().unwrap();
+        *state.dropped_calls.entry(method.to_string()).or_default() += count;
+    }
+
+    pub fn stop(&self) {
+        self.task.abort();
+    }
+}
+
+/// Minimal HTTP/1.1 loop: every request is a POST carrying one method call.
+async fn serve_connection(stream: TcpStream, state: &Mutex<MockState>) -> std::io::Result<()> {
+    let mut stream = BufReader::new(stream);
+
+    loop {
+        let mut content_length = 0;
+        let mut line = String::new();
+
+        if stream.read_line(&mut line).await? == 0 {
+            return Ok(());
+        }
+
+        loop {
+            line.clear();
+            stream.read_line(&mut line).await?;
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+
+        let mut body = vec![0; content_length];
+        stream.read_exact(&mut body).await?;
+
+        let Some(response) = handle_request(&String::from_utf8_lossy(&body), state) else {
+            return Ok(());
+        };
+        let head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/xml\r\nContent-Length: {}\r\n\r\n",
+            response.len()
+        );
+        stream.write_all(head.as_bytes()).await?;
+        stream.write_all(response.as_bytes()).await?;
+        stream.flush().await?;
+    }
+}
+
+/// Answer to one method call, `None` when the call is to be dropped.
+fn handle_request(body: &str, state: &Mutex<MockState>) -> Option<String> {
+    let mut state = state.lock().unwrap();
+    let result = match dxr::deserialize_xml::<MethodCall>(body) {
+        Ok(call) => {
+            let name = call.name().to_string();
+            if let Some(count) = state
+                .dropped_calls
+                .get_mut(&name)
+                .filter(|count| **count > 0)
+            {
+                *count -= 1;
+                return None;
+            }
+            dispatch(&name, &call.params(), &mut state)
+        }
+        Err(e) => Err(Fault::new(400, e.to_string())),
+    };
+
+    let xml = match result {
+        Ok(value) => dxr::serialize_xml(&MethodResponse::new(value)),
+        Err(fault) => dxr::serialize_xml(&FaultResponse::from(fault)),
+    };
+
+    Some(format!(
+        "<?xml version=\"1.0\"?>\n{}\n",
+        xml.unwrap_or_default()
+    ))
+}
+
+fn dispatch(name: &str, params: &[Value], state: &mut MockState) -> Result<Value, Fault> {
+    let invalid = |e: dxr::DxrError| Fault::new(400, e.to_string());
+
+    let value = match name {
+        "ping" => "NeosServer is alive\n".try_to_value(),
+        "listCategories" => HashMap::from([(
+            NEOS_CATEGORY.to_string(),
+            "Mixed Integer Linear Programming".to_string(),
+        )])
+        .try_to_value(),
+        "listSolversInCategory" => Solver::variants()
+            .iter()
+            .map(|solver| format!("{}:{}", solver.name(), NEOS_INPUT_TYPE))
+            .collect::<Vec<_>>()
+            .try_to_value(),
+        "submitJob" => {
+            let xml = String::try_from_params(params).map_err(invalid)?;
+            state.submit(&xml).try_to_value()
+        }
+        "getJobStatus" => {
+            let (number, password) = <(i32, String)>::try_from_params(params).map_err(invalid)?;
+            state.status(number, &password).try_to_value()
+        }
+        "getIntermediateResults" => {
+            let (number, password, offset) =
+                <(i32, String, i32)>::try_from_params(params).map_err(invalid)?;
+            state.job(number, &password)?;
+            (Vec::<u8>::new(), offset).try_to_value()
+        }
+        "getFinalResults" => {
+            let (number, password) = <(i32, String)>::try_from_params(params).map_err(invalid)?;
+            state
+                .job(number, &password)?
+                .output
+                .clone()
+                .into_bytes()
+                .try_to_value()
+        }
+        "killJob" => {
+            let (number, password, _) =
+                <(i32, String, String)>::try_from_params(params).map_err(invalid)?;
+            state.job(number, &password)?.killed = true;
+            format!("Job #{} is killed", number).try_to_value()
+        }
+        _ => return Err(Fault::new(404, format!("Unknown method {}", name))),
+    };
+
+    value.map_err(invalid)
+}
+
+impl MockState {
+    fn submit(&mut self, xml: &str) -> (i32, String) {
+        let Some(model) = between(xml, "<model><![CDATA[", "]]></model>") else {
+            return (0, "Missing model".to_string());
+        };
+
+        let output = match &self.answer {
+            MockAnswer::LocalSolver => match solve_model(model) {
+                Ok(output) => output,
+                Err(error) => return (0, error),
+            },
+            MockAnswer::Fixed(output) => output.clone(),
+        };
+
+        let number = self.next_number;
+        self.next_number += 1;
+        let password = format!("mock{}", number);
+
+        self.jobs.insert(
+            number,
+            MockJob {
+                password: password.clone(),
+                output,
+                killed: false,
+            },
+        );
+
+        (number, password)
+    }
+
+    fn status(&self, number: i32, password: &str) -> &'static str {
+        match self.jobs.get(&number) {
+            None => "Unknown Job",
+            Some(job) if job.password != password => "Bad Password",
+            Some(_) => "Done",
+        }
+    }
+
+    fn job(&mut self, number: i32, password: &str) -> Result<&mut MockJob, Fault> {
+        match self.jobs.get_mut(&number) {
+            Some(job) if job.password == password && !job.killed => Ok(job),
+            Some(job) if job.killed => Err(Fault::new(410, format!("Job {} was killed", number))),
+            _ => Err(Fault::new(403, format!("Unknown job {}", number))),
+        }
+    }
+}
+
+fn between<'a>(text: &'a str, open: &str, close: &str) -> Option<&'a str> {
+    let start = text.find(open)? + open.len();
+    let end = start + text[start..].find(close)?;
+    Some(&text[start..end])
+}
+
+/// Reads the `data;` section the templates render: grid size, endpoints,
+/// the node sets, pink pairs and the path limit `Ns`.
+fn scenario_from_model(model: &str) -> Option<Scenario> {
+    let data = &model[model.find("data;")?..];
+
+    let param = |name: &str| between(data, &format!("param {} :=", name), ";");
+    let numbers = |name: &str| {
+        param(name)?
+            .split_whitespace()
+            .map(|v| v.parse().ok())
+            .collect::<Option<Vec<usize>>>()
+    };
+    let cell = |name: &str| match numbers(name)?[..] {
+        [1, x, 2, y] => Some(Cell::new(x, y)),
+        _ => None,
+    };
+
+    let mut filled_cells = Vec::new();
+    for (set, r#type) in [
+        ("DISABLED_NODES", CellType::Green),
+        ("YELLOW_NODES", CellType::Yellow),
+        ("ORANGE_NODES", CellType::Orange),
+    ] {
+        let Some(nodes) = between(data, &format!("set {} :=", set), ";") else {
+            continue;
+        };
+        for node in nodes.split(')').filter_map(|n| n.trim().strip_prefix('(')) {
+            let (x, y) = node.split_once(',')?;
+            filled_cells.push(FilledCell {
+                x: x.trim().parse().ok()?,
+                y: y.trim().parse().ok()?,
+                r#type,
+            });
+        }
+    }
+
+    let mut pink_pairs = Vec::new();
+    for i in 1.. {
+        let Some(values) = numbers(&format!("pink_pair{}", i)) else {
+            break;
+        };
+        let [1, ax, 2, ay, 3, bx, 4, by] = values[..] else {
+            return None;
+        };
+        for (x, y) in [(ax, ay), (bx, by)] {
+            filled_cells.push(FilledCell {
+                x,
+                y,
+                r#type: CellType::Pink,
+            });
+        }
+        pink_pairs.push((Cell::new(ax, ay), Cell::new(bx, by)));
+    }
+
+    let params = param(MAX_PATHS_PARAM)
+        .and_then(|value| value.trim().parse().ok())
+        .map(|value| ParamValues::from([(MAX_PATHS_PARAM.to_string(), ParamValue::Int(value))]))
+        .unwrap_or_default();
+
+    Some(Scenario {
+        version: SCENARIO_VERSION,
+        width: param("rows")?.trim().parse().ok()?,
+        height: param("cols")?.trim().parse().ok()?,
+        cell_size: 20.0,
+        start_cell: Some(cell("start")?),
+        end_cell: Some(cell("finish")?),
+        filled_cells,
+        pink_pairs,
+        params,
+    })
+}
+
+/// Built-in template whose model part, everything before `data;`, matches
+/// the one of `model` when rendered for `field`. Surrounding whitespace is
+/// ignored, the NEOS input indents the model.
+fn template_of(model: &str, field: &Field) -> Option<Template> {
+    let head = model.split_once("data;")?.0.trim();
+    let templates = TemplateRegistry::builtin();
+
+    Template::variants()
+        .iter()
+        .find(|template| {
+            template
+                .render_ampl(field, &ParamValues::new(), &templates)
+                .is_ok_and(|rendered| {
+                    rendered.split_once("data;").map(|(h, _)| h.trim()) == Some(head)
+                })
+        })
+        .cloned()
+}
+
+/// Prints the solution the way the templates do, or the reason the job is
+/// rejected: models that are not built-in templates or that the local
+/// solver does not support.
+fn solve_model(model: &str) -> Result<String, String> {
+    const BANNER: &str = "MockSolver 1.0";
+
+    let scenario = scenario_from_model(model).ok_or("Failed to read the model data")?;
+    let params = scenario.params.clone();
+    let field = scenario
+        .into_field()
+        .map_err(|e| format!("Invalid model data: {}", e))?;
+    let template = template_of(model, &field).ok_or("Unknown model")?;
+
+    let paths = match local_solver::solve(&field, &template, &params, DisjointStrategy::Greedy) {
+        Ok(paths) => paths,
+        Err(AppError::NoPathFound) => return Ok(format!("{}: infeasible problem\n", BANNER)),
+        Err(e) => return Err(e.to_string()),
+    };
+
+    let mut output = String::new();
+    for path in &paths {
+        let links = path.cells().len().saturating_sub(1);
+        output.push_str(&format!(
+            "{}: optimal solution; objective {}\n",
+            BANNER, links
+        ));
+        output.push_str(&format!("\n--- Path {} ---\n", path.id + 1));
+        for link in path.cells().windows(2) {
+            output.push_str(&format!(
+                "  ({},{}) -> ({},{})\n",
+                link[0].x, link[0].y, link[1].x, link[1].y
+            ));
+        }
+    }
+
+    if template.max_paths(&params).is_some() {
+        output.push_str(&format!("\nTotal paths found: {}\n", paths.len()));
+    }
+    output.push_str("_total_solve_time = 0\n");
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use dxr_client::Url;
+
+    use super::*;
+    use crate::export::raster;
+    use crate::field::path::validation::validate_paths;
+    use crate::neos::api::NeosAPI;
+    use crate::neos::error::NeosError;
+    use crate::neos::response::NeosResponse;
+
+    fn field() -> Field {
+        let mut field = Field::new();
+        field.width = 6;
+        field.height = 4;
+        field.start_cell = Some(Cell::new(1, 1));
+        field.end_cell = Some(Cell::new(6, 4));
+        for y in 1..=3 {
+            field.filled_cells.insert(Cell::new(3, y), CellType::Green);
+        }
+        field
+    }
+
+    fn templates() -> TemplateRegistry {
+        TemplateRegistry::load("template")
+    }
+
+    async fn next_response(neos: &NeosAPI) -> NeosResponse {
+        for _ in 0..500 {
+            if let Ok(response) = neos.rx.try_recv() {
+                return response;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        panic!("No response from the mock server");
+    }
+
+    #[test]
+    fn test_scenario_from_model() {
+        let input = Template::Disabled
+            .generate_neos_input_string(
+                &field(),
+                &ParamValues::new(),
+                &Solver::Cbc,
+                "a@b.c",
+                &templates(),
+            )
+            .unwrap();
+        let model = between(&input, "<model><![CDATA[", "]]></model>").unwrap();
+
+        let scenario = scenario_from_model(model).unwrap();
+        assert_eq!((scenario.width, scenario.height), (6, 4));
+        assert_eq!(scenario.end_cell, Some(Cell::new(6, 4)));
+        assert_eq!(scenario.filled_cells.len(), 3);
+        assert!(scenario.params.is_empty());
+
+        let mut open = field();
+        open.filled_cells.clear();
+        let params = ParamValues::from([(MAX_PATHS_PARAM.to_string(), ParamValue::Int(2))]);
+        let input = Template::Multiple
+            .generate_neos_input_string(&open, &params, &Solver::Cbc, "a@b.c", &templates())
+            .unwrap();
+        let model = between(&input, "<model><![CDATA[", "]]></model>").unwrap();
+
+        assert_eq!(scenario_from_model(model).unwrap().params, params);
+        assert!(solve_model(model).unwrap().contains("Total paths found: 2"));
+    }
+
+    #[test]
+    fn test_pink_pairs_from_model() {
+        let mut field = field();
+        let pairs = [(Cell::new(4, 1), Cell::new(5, 2))];
+        field.replace_cells(field.filled_cells.clone(), &pairs);
+
+        let model = Template::Pink
+            .render_ampl(&field, &ParamValues::new(), &templates())
+            .unwrap();
+        let scenario = scenario_from_model(&model).unwrap();
+        assert_eq!(scenario.pink_pairs, pairs);
+        assert_eq!(scenario.into_field().unwrap().pink_pair_map.len(), 2);
+    }
+
+    #[test]
+    fn test_answers_follow_the_template() {
+        let mut field = field();
+        let mut cells = field.filled_cells.clone();
+        cells.insert(Cell::new(5, 3), CellType::Yellow);
+        cells.insert(Cell::new(4, 4), CellType::Orange);
+        field.replace_cells(cells, &[(Cell::new(4, 1), Cell::new(5, 2))]);
+        let params = ParamValues::from([(MAX_PATHS_PARAM.to_string(), ParamValue::Int(2))]);
+
+        for template in Template::variants() {
+            let model = template.render_ampl(&field, &params, &templates()).unwrap();
+
+            let supported = local_solver::SearchOptions::for_template(template).is_some();
+            let output = match solve_model(&model) {
+                Ok(output) => output,
+                Err(e) => {
+                    assert!(!supported, "{}: {}", template.name(), e);
+                    continue;
+                }
+            };
+            assert!(supported, "{} answered", template.name());
+
+            field.parse_all_paths(&output).unwrap();
+            let paths = field.paths.take().unwrap();
+            for report in validate_paths(&paths, &field, template, &params) {
+                assert!(
+                    report.violations.is_empty(),
+                    "{}: {:?}",
+                    template.name(),
+                    report.violations
+                );
+            }
+
+            let diagonal = paths[0]
+                .cells()
+                .windows(2)
+                .any(|w| w[0].x != w[1].x && w[0].y != w[1].y);
+            let four_connected = matches!(template, Template::Default | Template::Disabled);
+            assert_eq!(diagonal, !four_connected, "{}", template.name());
+            assert_eq!(
+                output.contains("Total paths found"),
+                template.max_paths(&params).is_some()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submit_parse_draw() {
+        let server = MockNeosServer::start("127.0.0.1:0", MockAnswer::LocalSolver)
+            .await
+            .unwrap();
+        let mut neos = NeosAPI::new(Url::parse(&server.url()).unwrap());
+
+        let mut field = field();
+        let input = Template::Disabled
+            .generate_neos_input_string(
+                &field,
+                &ParamValues::new(),
+                &Solver::Cbc,
+                "a@b.c",
+                &templates(),
+            )
+            .unwrap();
+        neos.submit_job(0, input);
+
+        let NeosResponse::JobCredentials(0, number, password) = next_response(&neos).await else {
+            panic!("Expected job credentials");
+        };
+        neos.poll_job(0, number, password);
+
+        let output = loop {
+            match next_response(&neos).await {
+                NeosResponse::JobOuput(0, output) => break output,
+                NeosResponse::JobStatus(0, _) => {}
+                _ => panic!("Unexpected response"),
+            }
+        };
+
+        field.parse_all_paths(&output).unwrap();
+        let path = &field.paths.as_ref().unwrap()[0];
+        assert_eq!(path.cells().first(), Some(&Cell::new(1, 1)));
+        assert_eq!(path.cells().last(), Some(&Cell::new(6, 4)));
+        assert!(path.cells().iter().all(|cell| cell.x != 3 || cell.y == 4));
+
+        let image = raster::render_field(&field, 10).unwrap();
+        assert_eq!(image.dimensions(), (60, 40));
+
+        server.stop();
+    }
+
+    #[tokio::test]
+    async fn test_polling_survives_dropped_call() {
+        let server = MockNeosServer::start("127.0.0.1:0", MockAnswer::Fixed("done".into()))
+            .await
+            .unwrap();
+        let mut neos = NeosAPI::new(Url::parse(&server.url()).unwrap());
+
+        neos.submit_job(0, "<model><![CDATA[]]></model>".to_string());
+        let NeosResponse::JobCredentials(0, number, password) = next_response(&neos).await else {
+            panic!("Expected job credentials");
+        };
+
+        server.drop_calls("getJobStatus", 1);
+        neos.poll_job(0, number, password);
+
+        let mut retried = false;
+        loop {
+            match next_response(&neos).await {
+                NeosResponse::JobStatus(0, status) => {
+                    retried |= status == "Connection lost, retrying…";
+                }
+                NeosResponse::JobOuput(0, output) => {
+                    assert_eq!(output, "done");
+                    break;
+                }
+                _ => panic!("Unexpected response"),
+            }
+        }
+        assert!(retried);
+
+        server.stop();
+    }
+
+    #[tokio::test]
+    async fn test_unknown_job() {
+        let server = MockNeosServer::start("127.0.0.1:0", MockAnswer::Fixed(String::new()))
+            .await
+            .unwrap();
+        let mut neos = NeosAPI::new(Url::parse(&server.url()).unwrap());
+
+        neos.poll_job(7, 42, "wrong".to_string());
+
+        loop {
+            match next_response(&neos).await {
+                NeosResponse::JobFailed(7, e) => {
+                    assert_eq!(e, NeosError::Rejected("Job 42: Unknown Job".to_string()));
+                    break;
+                }
+                NeosResponse::JobStatus(7, _) => {}
+                _ => panic!("Unexpected response"),
+            }
+        }
+
+        server.stop();
+    }
+}
@@ -1,3 +1,9 @@
 pub mod api;
+pub mod dialog;
+pub mod error;
+pub mod jobs;
+pub mod mock;
+pub mod report;
 pub mod response;
 pub mod solver;
+pub mod transport;
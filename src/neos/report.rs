@@ -0,0 +1,250 @@
+use nom::{
+    bytes::complete::{tag, take_until, take_while1},
+    character::complete::{char, digit1, space0},
+    combinator::{map_res, opt, rest},
+    number::complete::double,
+    sequence::{delimited, preceded, terminated},
+    IResult, Parser,
+};
+use serde::Serialize;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct PathReport {
+    pub number: usize,
+    pub objective: Option<f64>,
+    pub status: Option<String>,
+}
+
+/// Everything the templates print besides the path links.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct SolveReport {
+    /// First solver banner, e.g. `HiGHS 1.7.0: optimal solution; objective 24`.
+    pub solver_banner: Option<String>,
+    /// Status of the last solve.
+    pub status: Option<String>,
+    pub paths: Vec<PathReport>,
+    pub paths_found: Option<usize>,
+    /// Step at which a multiple path template stopped finding paths.
+    pub stopped_at_step: Option<usize>,
+    pub total_solve_time: Option<f64>,
+}
+
+impl SolveReport {
+    /// Sum of the objectives of all paths that reported one.
+    pub fn total_objective(&self) -> Option<f64> {
+        self.paths
+            .iter()
+            .filter_map(|path| path.objective)
+            .reduce(|a, b| a + b)
+    }
+
+    /// Paths the template reported as found, or the ones printed.
+    pub fn path_count(&self) -> usize {
+        self.paths_found.unwrap_or(self.paths.len())
+    }
+
+    /// Whether this result beats `other` in a solver comparison: more paths
+    /// win, the lower total objective only decides between equal counts.
+    pub fn is_better_than(&self, other: &SolveReport) -> bool {
+        match (self.total_objective(), other.total_objective()) {
+            (Some(objective), Some(other_objective)) => {
+                (self.path_count(), -objective) > (other.path_count(), -other_objective)
+            }
+            (objective, other_objective) => objective.is_some() && other_objective.is_none(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Banner<'a> {
+    line: &'a str,
+    status: &'a str,
+    objective: Option<f64>,
+}
+
+enum Line<'a> {
+    Banner(Banner<'a>),
+    PathHeader(usize),
+    PathsFound(usize),
+    NoMorePaths(usize),
+    SolveTime(f64),
+}
+
+fn number(input: &str) -> IResult<&str, usize> {
+    map_res(digit1, str::parse).parse(input)
+}
+
+fn solve_time(input: &str) -> IResult<&str, f64> {
+    preceded(
+        (tag("_total_solve_time"), space0, char('='), space0),
+        double,
+    )
+    .parse(input)
+}
+
+fn paths_found(input: &str) -> IResult<&str, usize> {
+    preceded(tag("Total paths found: "), number).parse(input)
+}
+
+fn no_more_paths(input: &str) -> IResult<&str, usize> {
+    preceded(tag("No more disjoint paths found at step "), number).parse(input)
+}
+
+fn path_header(input: &str) -> IResult<&str, usize> {
+    delimited(tag("--- Path "), number, tag(" ---")).parse(input)
+}
+
+fn objective(input: &str) -> IResult<&str, f64> {
+    preceded((take_until("objective "), tag("objective ")), double).parse(input)
+}
+
+/// `<Solver> <version>: <status>[; objective <value>]`
+fn banner(line: &str) -> IResult<&str, Banner<'_>> {
+    let (input, _) = (
+        take_while1(|c: char| c.is_ascii_alphabetic() || c == '-'),
+        char(' '),
+        take_while1(|c: char| c.is_ascii_digit()),
+        terminated(take_until(": "), tag(": ")),
+    )
+        .parse(line)?;
+    let (_, message) = rest(input)?;
+
+    let status = message.split(';').next().unwrap_or_default().trim();
+    let (_, objective) = opt(objective).parse(message)?;
+
+    Ok((
+        "",
+        Banner {
+            line,
+            status,
+            objective,
+        },
+    ))
+}
+
+fn line(input: &str) -> Option<Line<'_>> {
+    let input = input.trim();
+
+    if let Ok((_, n)) = path_header(input) {
+        Some(Line::PathHeader(n))
+    } else if let Ok((_, n)) = paths_found(input) {
+        Some(Line::PathsFound(n))
+    } else if let Ok((_, n)) = no_more_paths(input) {
+        Some(Line::NoMorePaths(n))
+    } else if let Ok((_, t)) = solve_time(input) {
+        Some(Line::SolveTime(t))
+    } else if let Ok((_, b)) = banner(input) {
+        Some(Line::Banner(b))
+    } else {
+        None
+    }
+}
+
+/// Each path is attributed to the solver banner printed right before it.
+pub fn parse_solve_report(output: &str) -> SolveReport {
+    let mut report = SolveReport::default();
+    let mut last_banner: Option<Banner> = None;
+
+    for l in output.lines().filter_map(line) {
+        match l {
+            Line::Banner(banner) => {
+                if report.solver_banner.is_none() {
+                    report.solver_banner = Some(banner.line.to_string());
+                }
+                report.status = Some(banner.status.to_string());
+                last_banner = Some(banner);
+            }
+            Line::PathHeader(number) => report.paths.push(PathReport {
+                number,
+                objective: last_banner.as_ref().and_then(|b| b.objective),
+                status: last_banner.as_ref().map(|b| b.status.to_string()),
+            }),
+            Line::PathsFound(n) => report.paths_found = Some(n),
+            Line::NoMorePaths(step) => report.stopped_at_step = Some(step),
+            Line::SolveTime(t) => report.total_solve_time = Some(t),
+        }
+    }
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_banner() {
+        let (_, b) = banner("HiGHS 1.7.0: optimal solution; objective 24.4").unwrap();
+        assert_eq!(b.status, "optimal solution");
+        assert_eq!(b.objective, Some(24.4));
+
+        let (_, b) = banner("fico-xpress 9.2.0 (41.01.01): Global search complete").unwrap();
+        assert_eq!(b.status, "Global search complete");
+        assert_eq!(b.objective, None);
+
+        assert!(banner("  (7,5) -> (8,5)").is_err());
+    }
+
+    #[test]
+    fn test_solve_time() {
+        assert_eq!(solve_time("_total_solve_time = 0.125"), Ok(("", 0.125)));
+    }
+
+    #[test]
+    fn test_parse_solve_report() {
+        let output = "\
+CBC 2.10.12: optimal solution; objective 12
+1 simplex iterations
+
+--- Path 1 ---
+  (1,1) -> (2,1)
+CBC 2.10.12: optimal solution; objective 14
+
+--- Path 2 ---
+  (1,1) -> (1,2)
+CBC 2.10.12: infeasible problem
+No more disjoint paths found at step 3
+
+Total paths found: 2
+_total_solve_time = 1.5
+";
+        let report = parse_solve_report(output);
+
+        assert_eq!(
+            report.solver_banner.as_deref(),
+            Some("CBC 2.10.12: optimal solution; objective 12")
+        );
+        assert_eq!(report.status.as_deref(), Some("infeasible problem"));
+        assert_eq!(report.paths.len(), 2);
+        assert_eq!(report.paths[0].objective, Some(12.0));
+        assert_eq!(report.paths[1].objective, Some(14.0));
+        assert_eq!(report.total_objective(), Some(26.0));
+        assert_eq!(report.paths_found, Some(2));
+        assert_eq!(report.stopped_at_step, Some(3));
+        assert_eq!(report.total_solve_time, Some(1.5));
+    }
+
+    #[test]
+    fn test_more_paths_rank_first() {
+        let report = |objectives: &[f64]| SolveReport {
+            paths: objectives
+                .iter()
+                .enumerate()
+                .map(|(i, objective)| PathReport {
+                    number: i + 1,
+                    objective: Some(*objective),
+                    status: None,
+                })
+                .collect(),
+            ..Default::default()
+        };
+
+        let two = report(&[12.0, 14.0]);
+        let one = report(&[10.0]);
+        assert!(two.is_better_than(&one));
+        assert!(!one.is_better_than(&two));
+        assert!(report(&[11.0, 14.0]).is_better_than(&two));
+        assert!(!two.is_better_than(&two));
+        assert!(one.is_better_than(&SolveReport::default()));
+    }
+}
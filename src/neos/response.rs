@@ -1,6 +1,18 @@
+use super::error::NeosError;
+use super::jobs::JobId;
+use super::solver::Solver;
+
 pub enum NeosResponse {
     Message(String),
-    Error(String),
-    JobCredentials(i32, String),
-    JobOuput(String),
+    Error(NeosError),
+    Solvers(Vec<Solver>),
+    // The variants below carry the local id of the job they belong to.
+    JobCredentials(JobId, i32, String),
+    JobFailed(JobId, NeosError),
+    JobStatus(JobId, String),
+    IntermediateOutput(JobId, String),
+    JobOuput(JobId, String),
+    JobKilled(JobId, String),
+    /// `killJob` failed, the job keeps running on NEOS.
+    KillFailed(JobId, NeosError),
 }
@@ -1,4 +1,6 @@
-#[derive(Clone, Copy, PartialEq)]
+use crate::consts::NEOS_INPUT_TYPE;
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum Solver {
     Cbc,
     Copt,
@@ -8,15 +10,25 @@ pub enum Solver {
     Minto,
     Mosek,
     Raposa,
+    /// Solver offered by NEOS that has no built-in variant.
+    Other(String),
 }
 
 impl Solver {
+    /// Built-in list, used until NEOS has been asked for its solvers.
     pub fn variants() -> &'static [Solver] {
         use Solver::*;
 
         &[Cbc, Copt, Cplex, FicoXpress, Highs, Minto, Mosek, Raposa]
     }
 
+    pub fn from_name(name: &str) -> Option<Solver> {
+        Self::variants()
+            .iter()
+            .find(|variant| variant.name() == name)
+            .cloned()
+    }
+
     pub fn name(&self) -> &str {
         match self {
             Solver::Cbc => "cbc",
@@ -27,6 +39,56 @@ impl Solver {
             Solver::Minto => "minto",
             Solver::Mosek => "mosek",
             Solver::Raposa => "raposa",
+            Solver::Other(name) => name,
+        }
+    }
+}
+
+/// Picks the solvers accepting AMPL input out of `listSolversInCategory`
+/// entries, which look like `HiGHS:AMPL` or `CPLEX:GAMS`.
+pub fn solvers_from_neos_list(entries: &[String]) -> Vec<Solver> {
+    let mut solvers = Vec::new();
+
+    for entry in entries {
+        let Some((name, input_type)) = entry.split_once(':') else {
+            continue;
+        };
+        if !input_type.eq_ignore_ascii_case(NEOS_INPUT_TYPE) {
+            continue;
         }
+
+        let name = name.to_lowercase();
+        let solver = Solver::from_name(&name).unwrap_or(Solver::Other(name));
+        if !solvers.contains(&solver) {
+            solvers.push(solver);
+        }
+    }
+
+    solvers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solvers_from_neos_list() {
+        let entries = [
+            "CBC:AMPL",
+            "CBC:GAMS",
+            "FICO-Xpress:AMPL",
+            "Gurobi:AMPL",
+            "bogus",
+        ]
+        .map(String::from);
+
+        assert_eq!(
+            solvers_from_neos_list(&entries),
+            vec![
+                Solver::Cbc,
+                Solver::FicoXpress,
+                Solver::Other("gurobi".to_string())
+            ]
+        );
     }
 }
@@ -0,0 +1,114 @@
+use std::time::Duration;
+
+use dxr::{TryFromValue, TryToParams};
+use dxr_client::Client;
+
+use crate::consts::NEOS_CALL_TIMEOUT;
+
+use super::error::NeosError;
+
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    pub attempts: u32,
+    /// Delay before the first retry, doubled after every further attempt.
+    pub backoff: Duration,
+    pub timeout: Duration,
+}
+
+impl RetryPolicy {
+    /// For calls that can safely be repeated.
+    pub const DEFAULT: RetryPolicy = RetryPolicy {
+        attempts: 3,
+        backoff: Duration::from_secs(1),
+        timeout: NEOS_CALL_TIMEOUT,
+    };
+
+    /// For `submitJob`, where repeating after a lost answer would submit the
+    /// job twice, and for the calls polling a job, whose loop retries them
+    /// with its own backoff.
+    pub const ONCE: RetryPolicy = RetryPolicy {
+        attempts: 1,
+        ..RetryPolicy::DEFAULT
+    };
+}
+
+/// Calls `method`, retrying transient failures according to `policy`.
+pub async fn call<P, R>(
+    client: &Client,
+    method: &str,
+    params: P,
+    policy: RetryPolicy,
+) -> Result<R, NeosError>
+where
+    P: TryToParams + Clone,
+    R: TryFromValue,
+{
+    let mut backoff = policy.backoff;
+    let mut attempt = 1;
+
+    loop {
+        let result =
+            match tokio::time::timeout(policy.timeout, client.call(method, params.clone())).await {
+                Ok(result) => result.map_err(NeosError::from),
+                Err(_) => Err(NeosError::Timeout),
+            };
+
+        match result {
+            Err(e) if e.is_transient() && attempt < policy.attempts => {
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dxr_client::{ClientBuilder, Url};
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    const FAST: RetryPolicy = RetryPolicy {
+        attempts: 2,
+        backoff: Duration::from_millis(10),
+        timeout: Duration::from_millis(200),
+    };
+
+    fn client(address: &str) -> Client {
+        ClientBuilder::new(Url::parse(&format!("http://{}", address)).unwrap()).build()
+    }
+
+    #[tokio::test]
+    async fn test_unreachable() {
+        // Bind and drop to get a port nobody listens on.
+        let address = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let result: Result<String, _> = call(&client(&address.to_string()), "ping", (), FAST).await;
+        assert!(matches!(result, Err(NeosError::Unreachable(_))));
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        // Accepts connections but never answers.
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let mut streams = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                streams.push(stream);
+            }
+        });
+
+        let result: Result<String, _> = call(&client(&address.to_string()), "ping", (), FAST).await;
+        assert_eq!(result, Err(NeosError::Timeout));
+
+        server.abort();
+    }
+}
@@ -0,0 +1,91 @@
+use eframe::egui::{Id, Modal, Ui};
+
+#[derive(Clone, Copy, PartialEq)]
+enum DialogKind {
+    Open,
+    SaveAs,
+}
+
+pub enum ScenarioAction {
+    Open(String),
+    Save(String),
+}
+
+pub struct ScenarioDialog {
+    kind: Option<DialogKind>,
+    path_buffer: String,
+}
+
+impl ScenarioDialog {
+    pub fn new() -> Self {
+        Self {
+            kind: None,
+            path_buffer: String::from("scenario.toml"),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.kind.is_some()
+    }
+
+    pub fn open(&mut self) {
+        self.kind = Some(DialogKind::Open);
+    }
+
+    pub fn save_as(&mut self) {
+        self.kind = Some(DialogKind::SaveAs);
+    }
+
+    pub fn close(&mut self) {
+        self.kind = None;
+    }
+
+    pub fn show(&mut self, ui: &mut Ui) -> Option<ScenarioAction> {
+        let kind = self.kind?;
+        let mut action = None;
+
+        Modal::new(Id::new("SCENARIO MODAL")).show(ui.ctx(), |ui| {
+            ui.set_width(250.0);
+
+            ui.heading(match kind {
+                DialogKind::Open => "Open scenario",
+                DialogKind::SaveAs => "Save scenario as",
+            });
+
+            ui.label("File:");
+            ui.text_edit_singleline(&mut self.path_buffer);
+
+            ui.add_space(10.0);
+
+            ui.horizontal(|ui| {
+                let confirm_label = match kind {
+                    DialogKind::Open => "Open",
+                    DialogKind::SaveAs => "Save",
+                };
+
+                if ui.button(confirm_label).clicked() && !self.path_buffer.is_empty() {
+                    let path = self.path_buffer.clone();
+                    action = Some(match kind {
+                        DialogKind::Open => ScenarioAction::Open(path),
+                        DialogKind::SaveAs => ScenarioAction::Save(path),
+                    });
+                    self.close();
+                }
+
+                ui.add_space(10.0);
+
+                if ui.button("Cancel").clicked() {
+                    self.close();
+                }
+            });
+        });
+
+        action
+    }
+}
+
+impl Default for ScenarioDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
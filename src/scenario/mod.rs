@@ -0,0 +1,247 @@
+pub mod dialog;
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::AppError,
+    field::{
+        cell::{Cell, CellType},
+        Field, MAX_FIELD_SIZE,
+    },
+    template::param::ParamValues,
+};
+
+/// Version written into every scenario file. Bump it whenever the layout of
+/// [`Scenario`] changes in a way older builds can't read.
+pub const SCENARIO_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FilledCell {
+    pub x: usize,
+    pub y: usize,
+    pub r#type: CellType,
+}
+
+fn default_cell_size() -> f32 {
+    Field::new().cell_size()
+}
+
+/// On-disk representation of a [`Field`] layout.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+    pub version: u32,
+    pub width: usize,
+    pub height: usize,
+    #[serde(default = "default_cell_size")]
+    pub cell_size: f32,
+    pub start_cell: Option<Cell>,
+    pub end_cell: Option<Cell>,
+    pub filled_cells: Vec<FilledCell>,
+    pub pink_pairs: Vec<(Cell, Cell)>,
+    /// Template parameters the scenario was last used with.
+    #[serde(default, skip_serializing_if = "ParamValues::is_empty")]
+    pub params: ParamValues,
+}
+
+impl Scenario {
+    pub fn from_field(field: &Field) -> Self {
+        let mut filled_cells = field
+            .filled_cells
+            .iter()
+            .map(|(cell, cell_type)| FilledCell {
+                x: cell.x,
+                y: cell.y,
+                r#type: *cell_type,
+            })
+            .collect::<Vec<_>>();
+        filled_cells.sort_by_key(|c| (c.y, c.x));
+
+        let mut pink_pairs = field
+            .unique_pink_pairs()
+            .into_iter()
+            .map(|(a, b)| (*a, *b))
+            .collect::<Vec<_>>();
+        pink_pairs.sort();
+
+        Self {
+            version: SCENARIO_VERSION,
+            width: field.width,
+            height: field.height,
+            cell_size: field.cell_size(),
+            start_cell: field.start_cell,
+            end_cell: field.end_cell,
+            filled_cells,
+            pink_pairs,
+            params: ParamValues::new(),
+        }
+    }
+
+    pub fn with_params(self, params: ParamValues) -> Self {
+        Self { params, ..self }
+    }
+
+    /// Whether both describe the same field and parameters, ignoring the
+    /// view-only cell size.
+    pub fn same_layout(&self, other: &Scenario) -> bool {
+        Self {
+            cell_size: other.cell_size,
+            ..self.clone()
+        } == *other
+    }
+
+    pub fn into_field(self) -> Result<Field, AppError> {
+        if self.version != SCENARIO_VERSION {
+            return Err(AppError::UnsupportedScenarioVersion(self.version));
+        }
+
+        if !(1..=MAX_FIELD_SIZE).contains(&self.width)
+            || !(1..=MAX_FIELD_SIZE).contains(&self.height)
+            || !self.cell_size.is_finite()
+        {
+            return Err(AppError::InvalidScenario);
+        }
+
+        let mut field = Field::new();
+        field.width = self.width;
+        field.height = self.height;
+        field.set_cell_size(self.cell_size);
+
+        let in_bounds = |cell: &Cell| field.contains(cell);
+
+        if !self.start_cell.iter().chain(&self.end_cell).all(in_bounds) {
+            return Err(AppError::InvalidScenario);
+        }
+
+        let mut filled_cells = HashMap::new();
+        for FilledCell { x, y, r#type } in self.filled_cells {
+            let cell = Cell::new(x, y);
+            if !in_bounds(&cell) {
+                return Err(AppError::InvalidScenario);
+            }
+            filled_cells.insert(cell, r#type);
+        }
+
+        let mut pink_pair_map = HashMap::new();
+        for (a, b) in self.pink_pairs {
+            let is_pink = |cell: &Cell| filled_cells.get(cell) == Some(&CellType::Pink);
+            if !is_pink(&a) || !is_pink(&b) {
+                return Err(AppError::InvalidScenario);
+            }
+            pink_pair_map.insert(a, b);
+            pink_pair_map.insert(b, a);
+        }
+
+        field.start_cell = self.start_cell;
+        field.end_cell = self.end_cell;
+        field.filled_cells = filled_cells;
+        field.pink_pair_map = pink_pair_map;
+
+        Ok(field)
+    }
+
+    pub fn load(path: &str) -> Result<Self, AppError> {
+        let content = std::fs::read_to_string(path).map_err(|_| AppError::FailedLoadScenario)?;
+        toml::from_str(&content).map_err(|_| AppError::FailedLoadScenario)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), AppError> {
+        let toml_str = toml::to_string_pretty(self).map_err(|_| AppError::FailedSaveScenario)?;
+        std::fs::write(path, toml_str).map_err(|_| AppError::FailedSaveScenario)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::template::param::ParamValue;
+
+    fn sample_field() -> Field {
+        let mut field = Field::new();
+        field.start_cell = Some(Cell::new(1, 1));
+        field.end_cell = Some(Cell::new(10, 5));
+        field.filled_cells.insert(Cell::new(3, 3), CellType::Yellow);
+        field.filled_cells.insert(Cell::new(5, 4), CellType::Green);
+        field.filled_cells.insert(Cell::new(6, 5), CellType::Green);
+        field.handle_add_pink_pair_constraint(Cell::new(5, 5));
+        field
+    }
+
+    #[test]
+    fn test_same_layout_ignores_cell_size() {
+        let mut field = sample_field();
+        let scenario = Scenario::from_field(&field);
+
+        field.set_cell_size(field.cell_size() + 10.0);
+        assert!(Scenario::from_field(&field).same_layout(&scenario));
+
+        let params = ParamValues::from([("turn_cost".to_string(), ParamValue::Int(3))]);
+        assert!(!Scenario::from_field(&field)
+            .with_params(params)
+            .same_layout(&scenario));
+
+        field.start_cell = None;
+        assert!(!Scenario::from_field(&field).same_layout(&scenario));
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let field = sample_field();
+        let params = ParamValues::from([("turn_cost".to_string(), ParamValue::Int(3))]);
+        let scenario = Scenario::from_field(&field).with_params(params);
+
+        let toml_str = toml::to_string_pretty(&scenario).unwrap();
+        let parsed: Scenario = toml::from_str(&toml_str).unwrap();
+        assert_eq!(parsed, scenario);
+
+        let restored = parsed.into_field().unwrap();
+        assert_eq!(restored.width, field.width);
+        assert_eq!(restored.height, field.height);
+        assert_eq!(restored.cell_size(), field.cell_size());
+        assert_eq!(restored.start_cell, field.start_cell);
+        assert_eq!(restored.end_cell, field.end_cell);
+        assert_eq!(restored.filled_cells, field.filled_cells);
+        assert_eq!(restored.pink_pair_map, field.pink_pair_map);
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let mut scenario = Scenario::from_field(&sample_field());
+        scenario.version = SCENARIO_VERSION + 1;
+
+        assert!(matches!(
+            scenario.into_field(),
+            Err(AppError::UnsupportedScenarioVersion(_))
+        ));
+    }
+
+    #[test]
+    fn test_out_of_bounds_cell() {
+        let mut scenario = Scenario::from_field(&sample_field());
+        scenario.filled_cells.push(FilledCell {
+            x: scenario.width + 1,
+            y: 1,
+            r#type: CellType::Green,
+        });
+
+        assert!(matches!(
+            scenario.into_field(),
+            Err(AppError::InvalidScenario)
+        ));
+    }
+
+    #[test]
+    fn test_non_finite_cell_size() {
+        let toml_str = toml::to_string_pretty(&Scenario::from_field(&sample_field()))
+            .unwrap()
+            .replace("cell_size = 20.0", "cell_size = nan");
+        let scenario: Scenario = toml::from_str(&toml_str).unwrap();
+        assert!(scenario.cell_size.is_nan());
+
+        assert!(matches!(
+            scenario.into_field(),
+            Err(AppError::InvalidScenario)
+        ));
+    }
+}
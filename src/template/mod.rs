@@ -1,16 +1,23 @@
+pub mod param;
 mod param_pink;
-
-use tera::Tera;
+pub mod registry;
 
 use crate::{
+    consts::{NEOS_CATEGORY, NEOS_INPUT_TYPE},
     error::AppError,
     field::{cell::CellType, Field},
+    local_solver::DEFAULT_MAX_PATHS,
     neos::solver::Solver,
     template::param_pink::PinkPairParam,
 };
 
-#[derive(Clone, Copy, Debug, PartialEq)]
-#[non_exhaustive]
+use param::{ParamSpec, ParamValues};
+use registry::TemplateRegistry;
+
+/// Name of the `Ns` parameter of the templates searching several paths.
+pub const MAX_PATHS_PARAM: &str = "Ns";
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum Template {
     Default,
     Eight,
@@ -19,12 +26,12 @@ pub enum Template {
     MultipleSections,
     MultipleSeparated,
     CornerCutting,
-    TurnCost(u32),
+    TurnCost,
     Pink,
-    Full {
-        max_yellow_nodes: usize,
-        max_orange_nodes: usize,
-    },
+    Full,
+    /// Template found in the template directory by its file name without
+    /// the `.tera` extension, see [`TemplateRegistry`].
+    Custom(String),
 }
 
 impl Template {
@@ -39,22 +46,84 @@ impl Template {
             MultipleSections,
             MultipleSeparated,
             CornerCutting,
-            TurnCost(0),
+            TurnCost,
             Pink,
-            Full {
-                max_yellow_nodes: 0,
-                max_orange_nodes: 0,
-            },
+            Full,
         ]
     }
 
-    pub fn generate_neos_input_string(
+    pub fn from_name(name: &str) -> Option<Template> {
+        Self::variants()
+            .iter()
+            .find(|variant| variant.name() == name)
+            .cloned()
+    }
+
+    /// Parameters of the built-in templates. Manifests may declare more,
+    /// see [`TemplateRegistry::params`].
+    pub fn params(&self) -> Vec<ParamSpec> {
+        let max_paths = ParamSpec::int(
+            MAX_PATHS_PARAM,
+            "Max paths",
+            1,
+            50,
+            DEFAULT_MAX_PATHS as i64,
+        );
+
+        match self {
+            Template::Multiple
+            | Template::MultipleSections
+            | Template::MultipleSeparated
+            | Template::CornerCutting => vec![max_paths],
+            Template::TurnCost => vec![ParamSpec::int("turn_cost", "Turn cost", 0, 10, 0)],
+            Template::Full => vec![
+                ParamSpec::int("max_yellow_nodes", "Max yellow", 0, 10, 0).with_alias("max-yellow"),
+                ParamSpec::int("max_orange_nodes", "Max orange", 0, 10, 0).with_alias("max-orange"),
+                max_paths,
+            ],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Value of the built-in integer parameter `name`, `None` when the
+    /// template has no such parameter.
+    pub fn int_param(&self, params: &ParamValues, name: &str) -> Option<i64> {
+        self.params()
+            .iter()
+            .find(|spec| spec.name == name)
+            .and_then(|spec| spec.value(params).as_int())
+    }
+
+    /// Number of disjoint paths the template searches for at most, `None`
+    /// for single path templates.
+    pub fn max_paths(&self, params: &ParamValues) -> Option<usize> {
+        self.int_param(params, MAX_PATHS_PARAM)
+            .map(|value| value as usize)
+    }
+
+    /// Renders the model with `params`, missing values take the defaults.
+    pub fn render_ampl(
         &self,
         field: &Field,
-        solver: &Solver,
-        email: &str,
+        params: &ParamValues,
+        templates: &TemplateRegistry,
     ) -> Result<String, AppError> {
-        let tera = Tera::new("template/*.tera").expect("Failed to load template");
+        if let Some(manifest) = templates.manifest(self.name()) {
+            let pink = (!field.pink_pair_map.is_empty()).then_some(CellType::Pink);
+            let unsupported = field
+                .filled_cells
+                .values()
+                .copied()
+                .chain(pink)
+                .find(|cell_type| !manifest.supports(*cell_type));
+
+            if let Some(cell_type) = unsupported {
+                return Err(AppError::UnsupportedCellType(
+                    templates.display_name(self).to_string(),
+                    cell_type,
+                ));
+            }
+        }
 
         let mut context = tera::Context::new();
 
@@ -78,17 +147,8 @@ impl Template {
         let pink_pairs = field.unique_pink_pairs();
         context.insert("pink_pairs", &PinkPairParam::new(pink_pairs));
 
-        if let Template::TurnCost(turn_cost) = self {
-            context.insert("turn_cost", turn_cost);
-        }
-
-        if let Template::Full {
-            max_yellow_nodes,
-            max_orange_nodes,
-        } = self
-        {
-            context.insert("max_yellow_nodes", max_yellow_nodes);
-            context.insert("max_orange_nodes", max_orange_nodes);
+        for spec in templates.params(self) {
+            context.insert(&spec.name, &spec.value(params));
         }
 
         for (name, block_cell_type) in [
@@ -108,16 +168,25 @@ impl Template {
             );
         }
 
-        let ampl_code = tera
-            .render(&format!("{}.tera", self.name()), &context)
-            .map_err(|_| AppError::FailedRenderFile)?;
+        templates.render(self.name(), &context)
+    }
+
+    pub fn generate_neos_input_string(
+        &self,
+        field: &Field,
+        params: &ParamValues,
+        solver: &Solver,
+        email: &str,
+        templates: &TemplateRegistry,
+    ) -> Result<String, AppError> {
+        let ampl_code = self.render_ampl(field, params, templates)?;
 
         let xml_input = format!(
             "
             <MyProblem>
-                <category>milp</category>
+                <category>{}</category>
                 <solver>{}</solver>
-                <inputType>AMPL</inputType>
+                <inputType>{}</inputType>
                 <priority>long</priority>
                 <email>{}</email>
                 <model><![CDATA[
@@ -128,7 +197,9 @@ impl Template {
                 <comments></comments>
             </MyProblem>
             ",
+            NEOS_CATEGORY,
             solver.name(),
+            NEOS_INPUT_TYPE,
             email,
             ampl_code
         );
@@ -145,9 +216,48 @@ impl Template {
             Template::MultipleSections => "path_multiple_sections",
             Template::MultipleSeparated => "path_multiple_separated",
             Template::CornerCutting => "path_corner_cutting",
-            Template::TurnCost(_) => "path_turn_cost",
+            Template::TurnCost => "path_turn_cost",
             Template::Pink => "path_pink",
-            Template::Full { .. } => "path_full",
+            Template::Full => "path_full",
+            Template::Custom(name) => name,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::field::cell::Cell;
+
+    #[test]
+    fn test_pink_blocks() {
+        let mut field = Field::new();
+        field.width = 6;
+        field.height = 4;
+        field.start_cell = Some(Cell::new(1, 1));
+        field.end_cell = Some(Cell::new(6, 4));
+
+        let render = |field: &Field| {
+            Template::Pink
+                .render_ampl(field, &ParamValues::new(), &TemplateRegistry::builtin())
+                .unwrap()
+        };
+
+        let model = render(&field);
+        assert!(!model.contains("pink_block"));
+        assert!(Template::Pink.params().is_empty());
+
+        let pairs = [
+            (Cell::new(2, 2), Cell::new(4, 3)),
+            (Cell::new(5, 1), Cell::new(5, 4)),
+        ];
+        field.replace_cells(HashMap::new(), &pairs);
+
+        let model = render(&field);
+        assert!(model.contains("subject to pink_block_1:"));
+        assert!(model.contains("subject to pink_block_2:"));
+        assert!(!model.contains("pink_block_3"));
+    }
+}
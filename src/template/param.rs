@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Parameter values by name, as stored in scenarios and job records.
+pub type ParamValues = BTreeMap<String, ParamValue>;
+
+/// Value of a template parameter as inserted into the Tera context.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ParamValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl ParamValue {
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            ParamValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum ParamKind {
+    Int {
+        min: i64,
+        max: i64,
+        default: i64,
+    },
+    Float {
+        min: f64,
+        max: f64,
+        default: f64,
+    },
+    Bool {
+        default: bool,
+    },
+    Enum {
+        options: Vec<String>,
+        default: String,
+    },
+}
+
+/// Declaration of a parameter in a template manifest, e.g.
+///
+/// ```toml
+/// [[params]]
+/// name = "max_turn_cost"
+/// label = "Turn cost"
+/// type = "int"
+/// min = 0
+/// max = 10
+/// default = 0
+/// ```
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct ParamSpec {
+    /// Name of the variable in the Tera context.
+    pub name: String,
+    pub label: Option<String>,
+    /// Further command line flags accepted for the parameter, without the
+    /// leading dashes.
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    #[serde(flatten)]
+    pub kind: ParamKind,
+}
+
+impl ParamSpec {
+    pub fn int(name: &str, label: &str, min: i64, max: i64, default: i64) -> Self {
+        Self {
+            name: name.to_string(),
+            label: Some(label.to_string()),
+            aliases: Vec::new(),
+            kind: ParamKind::Int { min, max, default },
+        }
+    }
+
+    pub fn with_alias(mut self, alias: &str) -> Self {
+        self.aliases.push(alias.to_string());
+        self
+    }
+
+    pub fn label(&self) -> &str {
+        self.label.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Command line flag setting the parameter, `turn_cost` becomes
+    /// `--turn-cost` and `Ns` becomes `--ns`.
+    pub fn flag(&self) -> String {
+        format!("--{}", self.name.replace('_', "-").to_lowercase())
+    }
+
+    /// Whether `flag` is [`Self::flag`] or one of the aliases.
+    pub fn matches_flag(&self, flag: &str) -> bool {
+        flag == self.flag()
+            || flag
+                .strip_prefix("--")
+                .is_some_and(|alias| self.aliases.iter().any(|a| a == alias))
+    }
+
+    pub fn default_value(&self) -> ParamValue {
+        match &self.kind {
+            ParamKind::Int { default, .. } => ParamValue::Int(*default),
+            ParamKind::Float { default, .. } => ParamValue::Float(*default),
+            ParamKind::Bool { default } => ParamValue::Bool(*default),
+            ParamKind::Enum { default, .. } => ParamValue::Text(default.clone()),
+        }
+    }
+
+    /// Value stored in `values`, or the default when it is missing, of the
+    /// wrong type or out of range.
+    pub fn value(&self, values: &ParamValues) -> ParamValue {
+        let value = match (&self.kind, values.get(&self.name)) {
+            (ParamKind::Float { .. }, Some(ParamValue::Int(value))) => {
+                Some(ParamValue::Float(*value as f64))
+            }
+            (_, value) => value.cloned(),
+        };
+
+        value
+            .filter(|value| self.accepts(value))
+            .unwrap_or_else(|| self.default_value())
+    }
+
+    pub fn accepts(&self, value: &ParamValue) -> bool {
+        match (&self.kind, value) {
+            (ParamKind::Int { min, max, .. }, ParamValue::Int(value)) => {
+                (min..=max).contains(&value)
+            }
+            (ParamKind::Float { min, max, .. }, ParamValue::Float(value)) => {
+                (min..=max).contains(&value)
+            }
+            (ParamKind::Bool { .. }, ParamValue::Bool(_)) => true,
+            (ParamKind::Enum { options, .. }, ParamValue::Text(value)) => options.contains(value),
+            _ => false,
+        }
+    }
+
+    /// Parses a value given on the command line.
+    pub fn parse(&self, text: &str) -> Option<ParamValue> {
+        let value = match &self.kind {
+            ParamKind::Int { .. } => ParamValue::Int(text.parse().ok()?),
+            ParamKind::Float { .. } => ParamValue::Float(text.parse().ok()?),
+            ParamKind::Bool { .. } => ParamValue::Bool(text.parse().ok()?),
+            ParamKind::Enum { .. } => ParamValue::Text(text.to_string()),
+        };
+
+        self.accepts(&value).then_some(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_values_fall_back_to_defaults() {
+        let spec = ParamSpec::int("turn_cost", "Turn cost", 0, 10, 2);
+        let mut values = ParamValues::new();
+        assert_eq!(spec.value(&values), ParamValue::Int(2));
+
+        values.insert("turn_cost".to_string(), ParamValue::Int(7));
+        assert_eq!(spec.value(&values), ParamValue::Int(7));
+
+        values.insert("turn_cost".to_string(), ParamValue::Int(11));
+        assert_eq!(spec.value(&values), ParamValue::Int(2));
+
+        values.insert("turn_cost".to_string(), ParamValue::Bool(true));
+        assert_eq!(spec.value(&values), ParamValue::Int(2));
+    }
+
+    #[test]
+    fn test_parse() {
+        let spec = ParamSpec::int("max_yellow_nodes", "Max yellow", 0, 10, 0);
+        assert_eq!(spec.flag(), "--max-yellow-nodes");
+        assert!(!spec.matches_flag("--max-yellow"));
+        let spec = spec.with_alias("max-yellow");
+        assert!(spec.matches_flag("--max-yellow"));
+        assert!(spec.matches_flag("--max-yellow-nodes"));
+        assert!(!spec.matches_flag("max-yellow"));
+        assert_eq!(spec.parse("3"), Some(ParamValue::Int(3)));
+        assert_eq!(spec.parse("30"), None);
+        assert_eq!(spec.parse("x"), None);
+
+        let spec = ParamSpec {
+            name: "mode".to_string(),
+            label: None,
+            aliases: Vec::new(),
+            kind: ParamKind::Enum {
+                options: vec!["fast".to_string(), "exact".to_string()],
+                default: "exact".to_string(),
+            },
+        };
+        assert_eq!(spec.parse("fast"), Some(ParamValue::Text("fast".into())));
+        assert_eq!(spec.parse("slow"), None);
+    }
+}
@@ -0,0 +1,403 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::Deserialize;
+use tera::Tera;
+
+use crate::error::AppError;
+use crate::field::cell::CellType;
+
+use super::param::{ParamKind, ParamSpec};
+use super::Template;
+
+/// Sidecar manifest `<name>.toml` next to `<name>.tera`.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Manifest {
+    /// Name shown in the template combo box, defaults to the file name.
+    pub name: Option<String>,
+    pub params: Vec<ParamSpec>,
+    /// Cell types the formulation handles, all of them when left out.
+    pub cell_types: Option<Vec<CellType>>,
+}
+
+impl Manifest {
+    /// Checks that every parameter has a usable range and default.
+    pub fn validate(&self) -> Result<(), String> {
+        for (i, spec) in self.params.iter().enumerate() {
+            if self.params[..i].iter().any(|other| other.name == spec.name) {
+                return Err(format!("parameter {} is declared twice", spec.name));
+            }
+
+            let valid = match &spec.kind {
+                ParamKind::Int { min, max, .. } => min <= max,
+                ParamKind::Float { min, max, .. } => min <= max,
+                ParamKind::Bool { .. } | ParamKind::Enum { .. } => true,
+            };
+            if !valid {
+                return Err(format!("parameter {} has min > max", spec.name));
+            }
+            if !spec.accepts(&spec.default_value()) {
+                return Err(format!(
+                    "default of parameter {} is out of range or not an option",
+                    spec.name
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn supports(&self, cell_type: CellType) -> bool {
+        self.cell_types
+            .as_ref()
+            .is_none_or(|types| types.contains(&cell_type))
+    }
+}
+
+/// Sources of the built-in templates, compiled in so that they render
+/// whatever the template directory contains.
+const BUILTIN_TEMPLATES: &[(&str, &str)] = &[
+    ("path.tera", include_str!("../../template/path.tera")),
+    ("path_8.tera", include_str!("../../template/path_8.tera")),
+    (
+        "path_disabled.tera",
+        include_str!("../../template/path_disabled.tera"),
+    ),
+    (
+        "path_multiple.tera",
+        include_str!("../../template/path_multiple.tera"),
+    ),
+    (
+        "path_multiple_sections.tera",
+        include_str!("../../template/path_multiple_sections.tera"),
+    ),
+    (
+        "path_multiple_separated.tera",
+        include_str!("../../template/path_multiple_separated.tera"),
+    ),
+    (
+        "path_corner_cutting.tera",
+        include_str!("../../template/path_corner_cutting.tera"),
+    ),
+    (
+        "path_turn_cost.tera",
+        include_str!("../../template/path_turn_cost.tera"),
+    ),
+    (
+        "path_pink.tera",
+        include_str!("../../template/path_pink.tera"),
+    ),
+    (
+        "path_full.tera",
+        include_str!("../../template/path_full.tera"),
+    ),
+];
+
+/// Built-in templates plus the templates discovered in a directory. Files
+/// named after a built-in template replace it, every other `.tera` file
+/// becomes a custom template.
+pub struct TemplateRegistry {
+    builtin: Tera,
+    /// Files loaded from the template directory.
+    files: Tera,
+    manifests: BTreeMap<String, Manifest>,
+    custom: Vec<String>,
+    /// Files that were skipped while loading.
+    warnings: Vec<AppError>,
+}
+
+impl TemplateRegistry {
+    /// Registry with only the built-in templates.
+    pub fn builtin() -> Self {
+        let mut builtin = Tera::default();
+        builtin
+            .add_raw_templates(BUILTIN_TEMPLATES.iter().copied())
+            .expect("built-in templates parse");
+
+        Self {
+            builtin,
+            files: Tera::default(),
+            manifests: BTreeMap::new(),
+            custom: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    /// Loads every template of `dir`. Files that fail to parse are skipped
+    /// and reported by [`TemplateRegistry::warnings`].
+    pub fn load(dir: &str) -> Self {
+        let mut registry = Self::builtin();
+
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) => {
+                registry.warn(format!("{}: {}", dir, e));
+                return registry;
+            }
+        };
+
+        let mut files = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "tera"))
+            .collect::<Vec<_>>();
+        files.sort();
+
+        for path in files {
+            let Some(name) = path.file_stem().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if let Err(e) = registry.load_file(&path, name) {
+                registry.warn(format!("{}: {}", path.display(), e));
+            }
+        }
+
+        registry
+    }
+
+    fn load_file(&mut self, path: &Path, name: &str) -> Result<(), String> {
+        let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+
+        let manifest_path = path.with_extension("toml");
+        let manifest = match std::fs::read_to_string(&manifest_path) {
+            Ok(content) => {
+                let manifest = toml::from_str::<Manifest>(&content)
+                    .map_err(|e| e.to_string())
+                    .and_then(|manifest| manifest.validate().map(|_| manifest))
+                    .map_err(|e| format!("{}: {}", manifest_path.display(), e))?;
+                Some(manifest)
+            }
+            Err(_) => None,
+        };
+
+        self.files
+            .add_raw_template(&format!("{}.tera", name), &content)
+            .map_err(|e| error_chain(&e))?;
+
+        if let Some(manifest) = manifest {
+            self.manifests.insert(name.to_string(), manifest);
+        }
+        if Template::from_name(name).is_none() {
+            self.custom.push(name.to_string());
+        }
+
+        Ok(())
+    }
+
+    fn warn(&mut self, message: String) {
+        self.warnings.push(AppError::FailedLoadTemplates(message));
+    }
+
+    pub fn warnings(&self) -> &[AppError] {
+        &self.warnings
+    }
+
+    pub fn manifest(&self, name: &str) -> Option<&Manifest> {
+        self.manifests.get(name)
+    }
+
+    pub fn custom_templates(&self) -> Vec<Template> {
+        self.custom.iter().cloned().map(Template::Custom).collect()
+    }
+
+    pub fn custom_template(&self, name: &str) -> Option<Template> {
+        self.custom
+            .iter()
+            .any(|n| n == name)
+            .then(|| Template::Custom(name.to_string()))
+    }
+
+    /// Built-in or custom template by file name.
+    pub fn resolve(&self, name: &str) -> Option<Template> {
+        Template::from_name(name).or_else(|| self.custom_template(name))
+    }
+
+    /// Parameters of the built-in template followed by the ones its manifest
+    /// adds, which lets a manifest extend a built-in template as well.
+    pub fn params(&self, template: &Template) -> Vec<ParamSpec> {
+        let mut params = template.params();
+        if let Some(manifest) = self.manifest(template.name()) {
+            for spec in &manifest.params {
+                if params.iter().all(|param| param.name != spec.name) {
+                    params.push(spec.clone());
+                }
+            }
+        }
+        params
+    }
+
+    pub fn display_name<'a>(&'a self, template: &'a Template) -> &'a str {
+        self.manifest(template.name())
+            .and_then(|manifest| manifest.name.as_deref())
+            .unwrap_or(template.name())
+    }
+
+    pub(super) fn render(&self, name: &str, context: &tera::Context) -> Result<String, AppError> {
+        let file = format!("{}.tera", name);
+        let tera = if self.files.get_template_names().any(|n| n == file) {
+            &self.files
+        } else {
+            &self.builtin
+        };
+
+        tera.render(&file, context)
+            .map_err(|e| AppError::FailedRenderTemplate(name.to_string(), error_chain(&e)))
+    }
+}
+
+/// Tera errors keep the useful part, e.g. the line that failed to parse,
+/// in their sources.
+fn error_chain(e: &dyn std::error::Error) -> String {
+    let mut message = e.to_string();
+    let mut source = e.source();
+    while let Some(e) = source {
+        message.push_str(&format!(": {}", e));
+        source = e.source();
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::{cell::Cell, Field};
+    use crate::template::param::{ParamKind, ParamValue, ParamValues};
+
+    #[test]
+    fn test_manifest() {
+        let manifest: Manifest = toml::from_str(
+            r#"
+            name = "Budgeted path"
+            cell_types = ["green", "yellow"]
+
+            [[params]]
+            name = "budget"
+            type = "int"
+            min = 0
+            max = 100
+            default = 10
+
+            [[params]]
+            name = "mode"
+            label = "Mode"
+            type = "enum"
+            options = ["fast", "exact"]
+            default = "exact"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.name.as_deref(), Some("Budgeted path"));
+        assert!(manifest.supports(CellType::Yellow));
+        assert!(!manifest.supports(CellType::Pink));
+        assert_eq!(
+            manifest.params[0].kind,
+            ParamKind::Int {
+                min: 0,
+                max: 100,
+                default: 10
+            }
+        );
+        assert_eq!(manifest.params[0].label(), "budget");
+        assert_eq!(
+            manifest.params[1].default_value(),
+            ParamValue::Text("exact".to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_manifests() {
+        let validate = |params: &str| toml::from_str::<Manifest>(params).unwrap().validate();
+
+        assert!(
+            validate("[[params]]\nname = 'a'\ntype = 'int'\nmin = 0\nmax = 5\ndefault = 5").is_ok()
+        );
+        assert!(
+            validate("[[params]]\nname = 'a'\ntype = 'int'\nmin = 5\nmax = 0\ndefault = 5")
+                .is_err()
+        );
+        assert!(validate(
+            "[[params]]\nname = 'a'\ntype = 'float'\nmin = 0.0\nmax = 1.0\ndefault = 2.0"
+        )
+        .is_err());
+        assert!(
+            validate("[[params]]\nname = 'a'\ntype = 'enum'\noptions = ['x']\ndefault = 'y'")
+                .is_err()
+        );
+        assert!(validate(
+            "[[params]]\nname = 'a'\ntype = 'bool'\ndefault = true\n\
+             [[params]]\nname = 'a'\ntype = 'bool'\ndefault = false"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_render_error_names_the_variable() {
+        let dir = std::env::temp_dir().join(format!("templates_render_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("custom.tera"), "param budget := {{ budget }};").unwrap();
+
+        let registry = TemplateRegistry::load(dir.to_str().unwrap());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let error = registry
+            .render("custom", &tera::Context::new())
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("custom"), "{}", error);
+        assert!(error.contains("budget"), "{}", error);
+    }
+
+    #[test]
+    fn test_builtin_templates_are_not_custom() {
+        let registry = TemplateRegistry::load("template");
+
+        assert!(registry.warnings().is_empty());
+        assert!(registry.custom_templates().is_empty());
+        assert_eq!(registry.resolve("path_8"), Some(Template::Eight));
+        assert_eq!(registry.resolve("nope"), None);
+    }
+
+    #[test]
+    fn test_bad_files_are_skipped() {
+        let dir = std::env::temp_dir().join(format!("templates_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("good.tera"), "param rows := {{ width }};").unwrap();
+        std::fs::write(dir.join("broken.tera"), "{% if %}").unwrap();
+        std::fs::write(dir.join("bad_manifest.tera"), "").unwrap();
+        std::fs::write(dir.join("bad_manifest.toml"), "params = 1").unwrap();
+
+        let registry = TemplateRegistry::load(dir.to_str().unwrap());
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(registry.warnings().len(), 2);
+        assert_eq!(
+            registry.custom_templates(),
+            vec![Template::Custom("good".to_string())]
+        );
+
+        let mut context = tera::Context::new();
+        context.insert("width", &3);
+        assert_eq!(
+            registry.render("good", &context).unwrap(),
+            "param rows := 3;"
+        );
+
+        // The directory has no built-in templates, they still render.
+        let mut field = Field::new();
+        field.start_cell = Some(Cell::new(1, 1));
+        field.end_cell = Some(Cell::new(2, 2));
+        assert!(Template::Eight
+            .render_ampl(&field, &ParamValues::new(), &registry)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_builtin_templates_are_embedded() {
+        let registry = TemplateRegistry::builtin();
+        for template in Template::variants() {
+            let file = format!("{}.tera", template.name());
+            assert!(registry.builtin.get_template_names().any(|n| n == file));
+        }
+    }
+}